@@ -1,4 +1,5 @@
 use crate::interner::Symbol;
+use crate::scanner::Span;
 
 #[derive(Clone, Copy)]
 pub(crate) struct Program<'ctx> {
@@ -7,28 +8,69 @@ pub(crate) struct Program<'ctx> {
 
 #[derive(Clone, Copy)]
 pub(crate) struct Decl<'ctx> {
+    /// Spans of the `///` comments right before the declaration, in order.
+    // FIXME: Nothing reads these yet, they're kept for a documentation tool.
+    #[allow(dead_code)]
+    pub(crate) doc_comments: &'ctx [Span],
     pub(crate) identifier: Symbol,
+    pub(crate) identifier_span: Span,
     pub(crate) value: &'ctx Expr<'ctx>,
+    /// Whether the declaration is a global defined with `mut`, which lives in
+    /// memory and can be assigned to, rather than a constant.
+    pub(crate) is_mutable: bool,
 }
 
 #[derive(Clone, Copy)]
-pub(crate) enum Expr<'ctx> {
+pub(crate) struct Expr<'ctx> {
+    pub(crate) id: NodeId,
+    pub(crate) kind: ExprKind<'ctx>,
+    pub(crate) span: Span,
+}
+
+/// Identifies an expression, so that passes can record what they learn about it
+/// in side tables instead of in the tree itself.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct NodeId(pub(crate) u32);
+
+#[derive(Clone, Copy)]
+pub(crate) enum ExprKind<'ctx> {
     Const(Const),
     BindRef(BindRef),
     BindDef(BindDef<'ctx>),
+    Assign(AssignExpr<'ctx>),
     Function(Function<'ctx>),
     If(IfExpr<'ctx>),
     For(ForExpr<'ctx>),
-    Break,
-    Continue,
+    Break(BreakExpr<'ctx>),
+    /// `continue`, which restarts the innermost loop, or the one with the
+    /// label if given.
+    Continue(Option<Label>),
+    Return(Option<&'ctx Expr<'ctx>>),
     Compound(CompoundExpr<'ctx>),
     Semi(&'ctx Expr<'ctx>),
-    FnCall(FnCallExpr),
+    FnCall(FnCallExpr<'ctx>),
+    Binary(BinaryExpr<'ctx>),
+    Unary(UnaryExpr<'ctx>),
+    Cast(CastExpr<'ctx>),
+    /// Stands in for code that failed to parse. Its syntax error is already
+    /// reported, so later passes accept it anywhere without reporting
+    /// errors of their own.
+    Error,
 }
 
 #[derive(Clone, Copy)]
 pub(crate) enum Const {
-    IntegerConstant { value: i32 },
+    Unit,
+    /// An integer constant of the type given by its suffix. Without a suffix,
+    /// the type is inferred from where the constant is used, defaulting to
+    /// `i32`.
+    IntegerConstant {
+        value: u64,
+        suffix: Option<IntType>,
+    },
+    BoolConstant {
+        value: bool,
+    },
 }
 
 #[derive(Clone, Copy)]
@@ -39,6 +81,20 @@ pub(crate) struct BindRef {
 #[derive(Clone, Copy)]
 pub(crate) struct BindDef<'ctx> {
     pub(crate) identifier: Symbol,
+    pub(crate) identifier_span: Span,
+    pub(crate) value: &'ctx Expr<'ctx>,
+    /// Whether the binding is defined with `mut`, which lets it be assigned
+    /// to.
+    pub(crate) is_mutable: bool,
+}
+
+/// `identifier = value`, or a compound assignment such as `identifier +=
+/// value`, which applies `op` to the binding's value and `value`.
+#[derive(Clone, Copy)]
+pub(crate) struct AssignExpr<'ctx> {
+    pub(crate) identifier: Symbol,
+    pub(crate) identifier_span: Span,
+    pub(crate) op: Option<BinaryOp>,
     pub(crate) value: &'ctx Expr<'ctx>,
 }
 
@@ -51,14 +107,91 @@ pub(crate) struct Function<'ctx> {
 
 #[derive(Clone, Copy)]
 pub(crate) struct Param {
-    identifier: Symbol,
-    ty: Type,
+    pub(crate) identifier: Symbol,
+    pub(crate) identifier_span: Span,
+    pub(crate) ty: Type,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub(crate) enum Type {
     Unit,
+    Bool,
+    Int(IntType),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum IntType {
+    I8,
+    I16,
     I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl IntType {
+    pub(crate) fn size_in_bytes(self) -> usize {
+        match self {
+            IntType::I8 | IntType::U8 => 1,
+            IntType::I16 | IntType::U16 => 2,
+            IntType::I32 | IntType::U32 => 4,
+            IntType::I64 | IntType::U64 => 8,
+        }
+    }
+
+    pub(crate) fn is_signed(self) -> bool {
+        matches!(
+            self,
+            IntType::I8 | IntType::I16 | IntType::I32 | IntType::I64
+        )
+    }
+
+    pub(crate) fn min_value(self) -> i128 {
+        if self.is_signed() {
+            -(1 << (8 * self.size_in_bytes() - 1))
+        } else {
+            0
+        }
+    }
+
+    pub(crate) fn max_value(self) -> i128 {
+        if self.is_signed() {
+            (1 << (8 * self.size_in_bytes() - 1)) - 1
+        } else {
+            (1 << (8 * self.size_in_bytes())) - 1
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            IntType::I8 => "i8",
+            IntType::I16 => "i16",
+            IntType::I32 => "i32",
+            IntType::I64 => "i64",
+            IntType::U8 => "u8",
+            IntType::U16 => "u16",
+            IntType::U32 => "u32",
+            IntType::U64 => "u64",
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<IntType> {
+        let int_type = match name {
+            "i8" => IntType::I8,
+            "i16" => IntType::I16,
+            "i32" => IntType::I32,
+            "i64" => IntType::I64,
+            "u8" => IntType::U8,
+            "u16" => IntType::U16,
+            "u32" => IntType::U32,
+            "u64" => IntType::U64,
+            _ => return None,
+        };
+
+        Some(int_type)
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -77,6 +210,9 @@ pub(crate) struct ElseIfBranch<'ctx> {
 
 #[derive(Clone, Copy)]
 pub(crate) struct ForExpr<'ctx> {
+    /// The name given to the loop with `label: for`, so that a `break` or
+    /// `continue` in a nested loop can refer to it.
+    pub(crate) label: Option<Label>,
     pub(crate) iteration: Option<ForIteration<'ctx>>,
     pub(crate) body: CompoundExpr<'ctx>,
 }
@@ -88,12 +224,58 @@ pub(crate) enum ForIteration<'ctx> {
     },
     Iterative {
         identifier: Symbol,
+        identifier_span: Span,
         start_expr: &'ctx Expr<'ctx>,
         end_expr: &'ctx Expr<'ctx>,
         range_kind: RangeKind,
+        /// How much the loop variable changes after each iteration, `1` if not
+        /// given. It must be positive: a step known at compile time that isn't
+        /// is an error, and one known only at run time makes the range empty.
+        step_expr: Option<&'ctx Expr<'ctx>>,
+        /// Whether the step is written with a leading `-`, which makes the loop
+        /// count down from the start to the end by `step_expr`.
+        is_descending: bool,
     },
 }
 
+/// `break`, which leaves the innermost loop, or the one with the label if
+/// given. It may carry the value of the loop it leaves, as long as the loop
+/// has no condition.
+#[derive(Clone, Copy)]
+pub(crate) struct BreakExpr<'ctx> {
+    pub(crate) label: Option<Label>,
+    pub(crate) value: Option<&'ctx Expr<'ctx>>,
+}
+
+impl BreakExpr<'_> {
+    /// The name that a `break` without a label leaves with, as in `break x`,
+    /// read as a label. The parser only takes it as one if a loop around it
+    /// has that label, so it's up to name resolution to tell whether `x` is a
+    /// binding or a label that doesn't exist.
+    pub(crate) fn value_as_label(&self) -> Option<Label> {
+        match (self.label, self.value) {
+            (
+                None,
+                Some(&Expr {
+                    kind: ExprKind::BindRef(bind_ref),
+                    span,
+                    ..
+                }),
+            ) => Some(Label {
+                identifier: bind_ref.identifier,
+                span,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Label {
+    pub(crate) identifier: Symbol,
+    pub(crate) span: Span,
+}
+
 #[derive(Clone, Copy)]
 pub(crate) enum RangeKind {
     Inclusive,
@@ -106,6 +288,55 @@ pub(crate) struct CompoundExpr<'ctx> {
 }
 
 #[derive(Clone, Copy)]
-pub(crate) struct FnCallExpr {
+pub(crate) struct FnCallExpr<'ctx> {
     pub(crate) identifier: Symbol,
+    pub(crate) identifier_span: Span,
+    pub(crate) arguments: &'ctx [Expr<'ctx>],
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct BinaryExpr<'ctx> {
+    pub(crate) op: BinaryOp,
+    pub(crate) lhs: &'ctx Expr<'ctx>,
+    pub(crate) rhs: &'ctx Expr<'ctx>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum BinaryOp {
+    Mul,
+    Div,
+    Add,
+    Sub,
+    Shl,
+    Shr,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    /// `&&`, which only evaluates its right operand if the left one is `true`.
+    LogicalAnd,
+    /// `||`, which only evaluates its right operand if the left one is `false`.
+    LogicalOr,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct UnaryExpr<'ctx> {
+    pub(crate) op: UnaryOp,
+    pub(crate) operand: &'ctx Expr<'ctx>,
+}
+
+/// `operand as ty`, which converts an integer or a `bool` to an integer type,
+/// truncating or extending it as needed.
+#[derive(Clone, Copy)]
+pub(crate) struct CastExpr<'ctx> {
+    pub(crate) operand: &'ctx Expr<'ctx>,
+    pub(crate) ty: Type,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum UnaryOp {
+    Neg,
+    Not,
 }
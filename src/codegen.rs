@@ -2,43 +2,166 @@ use std::collections::HashMap;
 use std::fmt;
 
 use crate::ast::{
-    BindDef, BindRef, CompoundExpr, Const, Decl, Expr, FnCallExpr, ForExpr, ForIteration, Function,
-    IfExpr, Program, RangeKind,
+    AssignExpr, BinaryExpr, BinaryOp, BindDef, BindRef, BreakExpr, CastExpr, CompoundExpr, Const,
+    Decl, Expr, ExprKind, FnCallExpr, ForExpr, ForIteration, Function, IfExpr, IntType, NodeId,
+    Param, Program, RangeKind, Type, UnaryExpr, UnaryOp,
 };
 use crate::compiler_context::CompilerContext;
+use crate::const_eval::{ConstEvaluator, EvalError};
+use crate::diagnostic::Diagnostic;
 use crate::interner::Symbol;
+use crate::labels::LoopTargets;
+use crate::scanner::{BytePos, Span};
+use crate::typeck::{Ty, TypeTable};
+
+/// What the generated assembly is for.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum OutputKind {
+    /// Just the code and data of the program, without the directives around
+    /// them.
+    Fragment,
+    /// A complete file for the assembler, which exports the functions and
+    /// gives `main` the exit code as its return value, to be linked with libc.
+    File,
+    /// Like `File`, along with a `_start` entry point that calls `main` and
+    /// exits with what it returns, so that it can be linked without libc.
+    FreestandingFile,
+}
 
 pub(crate) struct CodeGen<'ctx> {
     ctx: &'ctx CompilerContext,
+    output_kind: OutputKind,
+    types: &'ctx TypeTable,
+    loop_targets: &'ctx LoopTargets,
+    const_eval: ConstEvaluator<'ctx>,
     label_counter: u64,
     allocated_stack_bytes: usize,
+    /// Bytes at the bottom of the current function's frame for the arguments
+    /// that its calls pass on the stack, enough for the call with the most.
+    outgoing_arg_bytes: usize,
     scope_stack: Vec<Scope>,
+    /// Values of the top-level constants, which are put in place of their
+    /// uses.
+    constant_by_symbol: HashMap<Symbol, i64>,
+    /// Labels of the loops being generated, by the id of their `for`
+    /// expression.
+    labels_by_loop: HashMap<NodeId, LoopLabels>,
+    /// Label of the current function's epilogue, made on the first `return`
+    /// that needs it.
+    return_label: Option<Symbol>,
 }
 
 #[derive(Default)]
 pub(crate) struct Scope {
-    memory_offset_by_symbol: HashMap<Symbol, usize>,
-    innermost_start_label: Option<Symbol>,
-    innermost_exit_label: Option<Symbol>,
+    /// Maps each binding to its memory location, as an offset from `rbp`.
+    memory_offset_by_symbol: HashMap<Symbol, i32>,
+}
+
+/// Where `continue` and `break` jump to in a loop.
+#[derive(Clone, Copy)]
+struct LoopLabels {
+    start: Symbol,
+    exit: Symbol,
+    /// Whether the loop steps a variable, which `continue` doesn't skip.
+    is_iterative: bool,
+    /// Label of the code that steps the loop variable, made on the first
+    /// `continue` that needs it.
+    step: Option<Symbol>,
 }
 
 impl<'ctx> CodeGen<'ctx> {
-    pub(crate) fn new(ctx: &'ctx CompilerContext) -> CodeGen<'ctx> {
+    /// Registers that carry the first integer arguments of a call, per the
+    /// System V ABI.
+    const ARG_REGS: [Reg; 6] = [Reg::Rdi, Reg::Rsi, Reg::Rdx, Reg::Rcx, Reg::R8, Reg::R9];
+    /// Number of Linux's `exit` system call.
+    const EXIT_SYSCALL: i64 = 60;
+
+    pub(crate) fn new(
+        ctx: &'ctx CompilerContext,
+        types: &'ctx TypeTable,
+        loop_targets: &'ctx LoopTargets,
+        const_eval: ConstEvaluator<'ctx>,
+        output_kind: OutputKind,
+    ) -> CodeGen<'ctx> {
         CodeGen {
             ctx,
+            output_kind,
+            types,
+            loop_targets,
+            const_eval,
             label_counter: 0,
             allocated_stack_bytes: 0,
+            outgoing_arg_bytes: 0,
             scope_stack: vec![],
+            constant_by_symbol: HashMap::new(),
+            labels_by_loop: HashMap::new(),
+            return_label: None,
         }
     }
 
     pub(crate) fn gen_program(&mut self, program: Program) -> X86Program {
+        let mut globals = vec![];
+
+        // Constants are put in place of their uses, so their values are needed
+        // before any function is generated.
+        for decl in program.decls {
+            if matches!(decl.value.kind, ExprKind::Function(_) | ExprKind::Error)
+                || self.holds_no_value(decl.value.id)
+            {
+                continue;
+            }
+
+            let result = if decl.is_mutable {
+                self.const_eval.eval(decl.value)
+            } else {
+                self.const_eval.value_of_constant(decl.identifier)
+            };
+
+            // Values are kept as the bits of a 64-bit integer.
+            let value = match result {
+                Ok(value) => value as i64,
+                Err(error) => {
+                    self.report_non_constant_value(decl, error);
+
+                    continue;
+                }
+            };
+
+            if decl.is_mutable {
+                globals.push((decl.identifier, self.size_of_value(decl.value.id), value));
+            } else {
+                self.constant_by_symbol.insert(decl.identifier, value);
+            }
+        }
+
         let mut generated_insts = vec![];
 
+        if self.output_kind != OutputKind::Fragment {
+            self.check_main(program);
+
+            generated_insts.push(Inst::IntelSyntax);
+            generated_insts.push(Inst::Section {
+                section: Section::Text,
+            });
+        }
+
         for decl in program.decls {
             generated_insts.extend(self.gen_decl(decl));
         }
 
+        if self.output_kind == OutputKind::FreestandingFile {
+            generated_insts.extend(self.gen_start());
+        }
+
+        generated_insts.extend(self.gen_globals(&globals));
+
+        // Marks the stack as not executable, which linkers otherwise warn about.
+        if self.output_kind != OutputKind::Fragment {
+            generated_insts.push(Inst::Section {
+                section: Section::NoteGnuStack,
+            });
+        }
+
         X86Program {
             ctx: self.ctx,
             instructions: generated_insts,
@@ -46,47 +169,233 @@ impl<'ctx> CodeGen<'ctx> {
     }
 
     fn gen_decl(&mut self, decl: &Decl) -> Vec<Inst> {
-        let mut decl_insts = vec![Inst::Label {
+        // Constants and globals have no code of their own.
+        let ExprKind::Function(function) = decl.value.kind else {
+            return vec![];
+        };
+
+        let mut decl_insts = vec![];
+
+        if self.output_kind != OutputKind::Fragment {
+            decl_insts.push(Inst::Globl {
+                name: decl.identifier,
+            });
+        }
+
+        decl_insts.push(Inst::Label {
             name: decl.identifier,
-        }];
+        });
 
-        let value_insts = self.parse_top_level_expr(decl.value);
-        decl_insts.extend(value_insts);
+        // The value of `main` is the program's exit code, which is zero when it
+        // returns nothing.
+        let exits_with_zero = self.output_kind != OutputKind::Fragment
+            && self.ctx.resolve_symbol(decl.identifier) == "main"
+            && function.return_type == Type::Unit;
+
+        decl_insts.extend(self.gen_function(function, exits_with_zero));
 
         decl_insts
     }
 
-    fn parse_top_level_expr(&mut self, expr: &Expr) -> Vec<Inst> {
-        match expr {
-            Expr::Function(Function { body, .. }) => self.gen_function(*body),
-            _ => todo!("other top-level exprs"),
+    /// Checks that `main` can be the entry point of the program: it takes no
+    /// parameters, and its return value, if any, is an `i32` exit code.
+    fn check_main(&self, program: Program) {
+        let main_decl = program
+            .decls
+            .iter()
+            .find(|decl| self.ctx.resolve_symbol(decl.identifier) == "main");
+
+        let Some(main_decl) = main_decl else {
+            // Only `_start` needs a `main` here, as libc's own entry point is
+            // resolved when linking.
+            if self.output_kind == OutputKind::FreestandingFile {
+                self.ctx.report(Diagnostic::error(
+                    "`_start` needs a `main` function to call",
+                    Span {
+                        start: BytePos(0),
+                        end: BytePos(0),
+                    },
+                ));
+            }
+
+            return;
+        };
+
+        let ExprKind::Function(function) = main_decl.value.kind else {
+            self.ctx.report(Diagnostic::error(
+                "`main` must be a function",
+                main_decl.identifier_span,
+            ));
+
+            return;
+        };
+
+        if !function.parameters.is_empty() {
+            self.ctx.report(Diagnostic::error(
+                "`main` can't take parameters",
+                main_decl.identifier_span,
+            ));
+        }
+
+        if !matches!(function.return_type, Type::Unit | Type::Int(IntType::I32)) {
+            self.ctx.report(
+                Diagnostic::error(
+                    "`main` must return `i32` or `()`",
+                    main_decl.identifier_span,
+                )
+                .with_note("the value it returns is the exit code of the program"),
+            );
+        }
+    }
+
+    /// Makes the `_start` entry point, which calls `main` and passes the value
+    /// it returns to the `exit` system call.
+    fn gen_start(&self) -> Vec<Inst> {
+        let start = self.ctx.get_or_intern_str("_start");
+
+        vec![
+            Inst::Globl { name: start },
+            Inst::Label { name: start },
+            Inst::Call {
+                label: self.ctx.get_or_intern_str("main"),
+            },
+            Inst::Mov {
+                target: Arg::Reg(Reg::Rdi, Size::Dword),
+                source: Arg::Reg(Reg::Rax, Size::Dword),
+            },
+            Inst::Mov {
+                target: Arg::Reg(Reg::Rax, Size::Dword),
+                source: Arg::Imm(Self::EXIT_SYSCALL),
+            },
+            Inst::Syscall,
+        ]
+    }
+
+    fn report_non_constant_value(&self, decl: &Decl, error: EvalError) {
+        let name = self.ctx.resolve_symbol(decl.identifier);
+
+        match error {
+            EvalError::NotConstant(span) => {
+                let mut diagnostic = Diagnostic::error(
+                    format!("the value of `{}` must be known at compile time", name),
+                    decl.value.span,
+                );
+
+                // What isn't known may be deep in a function that the value calls.
+                if span != decl.value.span {
+                    diagnostic = diagnostic.with_label(span, "this is only known at run time");
+                }
+
+                self.ctx.report(diagnostic);
+            }
+            EvalError::LimitReached => self.ctx.report(
+                Diagnostic::error(
+                    format!("the value of `{}` takes too long to evaluate", name),
+                    decl.value.span,
+                )
+                .with_note(format!(
+                    "evaluation stops after {} steps or {} nested calls",
+                    ConstEvaluator::STEP_LIMIT,
+                    ConstEvaluator::CALL_DEPTH_LIMIT
+                )),
+            ),
+            EvalError::Reported => {}
+        }
+    }
+
+    /// Lays out the globals, each under its own name, in the data section if
+    /// they have an initial value, or in the `.bss` section otherwise.
+    fn gen_globals(&self, globals: &[(Symbol, Size, i64)]) -> Vec<Inst> {
+        let mut data_insts = vec![];
+        let mut bss_insts = vec![];
+
+        for &(name, size, value) in globals {
+            let (insts, value_inst) = if value == 0 {
+                (
+                    &mut bss_insts,
+                    Inst::Zero {
+                        bytes: size.in_bytes(),
+                    },
+                )
+            } else {
+                (&mut data_insts, Inst::Data { size, value })
+            };
+
+            insts.push(Inst::Align {
+                bytes: size.in_bytes(),
+            });
+            insts.push(Inst::Label { name });
+            insts.push(value_inst);
         }
+
+        let mut insts = vec![];
+
+        if !data_insts.is_empty() {
+            insts.push(Inst::Section {
+                section: Section::Data,
+            });
+            insts.extend(data_insts);
+        }
+
+        if !bss_insts.is_empty() {
+            insts.push(Inst::Section {
+                section: Section::Bss,
+            });
+            insts.extend(bss_insts);
+        }
+
+        insts
     }
 
-    fn gen_function(&mut self, body: CompoundExpr) -> Vec<Inst> {
+    fn gen_function(&mut self, function: Function, exits_with_zero: bool) -> Vec<Inst> {
         self.enter_scope();
 
+        // Code generation only uses caller-saved registers, so `rbp` is the only
+        // one that the prologue saves.
         let mut insts = vec![
             Inst::Push { source: Reg::Rbp },
             Inst::Mov {
-                target: Arg::Reg(Reg::Rbp),
-                source: Arg::Reg(Reg::Rsp),
+                target: Arg::Reg(Reg::Rbp, Size::Qword),
+                source: Arg::Reg(Reg::Rsp, Size::Qword),
             },
         ];
 
-        let mut body_insts = self.gen_compound_expr(body);
+        self.return_label = None;
+
+        let mut body_insts = self.gen_parameters(function.parameters);
+        body_insts.extend(self.gen_compound_expr(function.body));
+
+        // Every `return` jumps here, while the body's trailing expression falls through
+        // with its value already in `eax`.
+        if let Some(return_label) = self.return_label.take() {
+            body_insts.push(Inst::Label { name: return_label });
+        }
+
+        if exits_with_zero {
+            body_insts.push(Inst::Mov {
+                target: Arg::Reg(Reg::Rax, Size::Dword),
+                source: Arg::Imm(0),
+            });
+        }
 
-        if self.allocated_stack_bytes != 0 {
-            // FIXME: Should not cast allocated_stack_bytes to i32.
+        // The call to this function pushed the return address, and the prologue
+        // pushed `rbp`, so `rsp` is 16-byte aligned here. Keeping the frame a
+        // multiple of 16 bytes keeps it aligned at every call the body makes, as
+        // the ABI requires.
+        let frame_bytes =
+            (self.allocated_stack_bytes + self.outgoing_arg_bytes).next_multiple_of(16);
+
+        if frame_bytes != 0 {
+            // FIXME: Should not cast frame_bytes to i64.
             insts.push(Inst::Sub {
-                target: Arg::Reg(Reg::Rsp),
-                source: Arg::Imm(self.allocated_stack_bytes as i32),
+                target: Arg::Reg(Reg::Rsp, Size::Qword),
+                source: Arg::Imm(frame_bytes as i64),
             });
 
-            // FIXME: Should not cast allocated_stack_bytes to i32.
+            // FIXME: Should not cast frame_bytes to i64.
             body_insts.push(Inst::Add {
-                target: Arg::Reg(Reg::Rsp),
-                source: Arg::Imm(self.allocated_stack_bytes as i32),
+                target: Arg::Reg(Reg::Rsp, Size::Qword),
+                source: Arg::Imm(frame_bytes as i64),
             });
         }
 
@@ -100,33 +409,102 @@ impl<'ctx> CodeGen<'ctx> {
         insts
     }
 
+    /// Binds the function's parameters as locals. Following the System V ABI,
+    /// the first six are passed in registers, which get spilled to the
+    /// stack frame, and the rest are pushed by the caller right above the
+    /// return address. Parameters of type `()` hold no value, so
+    /// they aren't passed at all.
+    fn gen_parameters(&mut self, parameters: &[Param]) -> Vec<Inst> {
+        let mut insts = vec![];
+
+        let passed_params = parameters
+            .iter()
+            .filter_map(|param| Some((param, Size::of_type(param.ty)?)));
+
+        for (param_idx, (param, size)) in passed_params.enumerate() {
+            let offset = if let Some(&arg_reg) = CodeGen::ARG_REGS.get(param_idx) {
+                let offset = self.allocate_stack_slot(size);
+
+                insts.push(Inst::Mov {
+                    target: Arg::MemOffset {
+                        base: Reg::Rbp,
+                        offset,
+                        size,
+                    },
+                    source: Arg::Reg(arg_reg, size),
+                });
+
+                offset
+            } else {
+                // Skip the saved `rbp` and the return address; each stack argument takes up
+                // an eightbyte.
+                let stack_arg_idx = param_idx - CodeGen::ARG_REGS.len();
+
+                // FIXME: Should not cast stack_arg_idx to i32.
+                16 + 8 * stack_arg_idx as i32
+            };
+
+            self.insert_in_scope(param.identifier, offset);
+        }
+
+        insts
+    }
+
     fn gen_expr(&mut self, expr: &Expr) -> Vec<Inst> {
-        match expr {
-            Expr::Semi(expr) => self.gen_expr(expr),
-            Expr::Const(constant) => self.gen_constant_expr(*constant),
-            Expr::If(if_expr) => self.gen_if_expr(*if_expr),
-            Expr::For(for_expr) => self.gen_for_expr(*for_expr),
-            Expr::Break => self.gen_break_expr(),
-            Expr::Continue => self.gen_continue_expr(),
-            Expr::BindDef(bind_def) => self.gen_bind_def_expr(*bind_def),
-            Expr::BindRef(bind_ref) => self.gen_bind_ref_expr(*bind_ref),
-            Expr::Compound(compound_expr) => self.gen_compound_expr(*compound_expr),
-            Expr::FnCall(fn_call_expr) => self.gen_fn_call_expr(*fn_call_expr),
-            Expr::Function(_) => unimplemented!(),
-        }
-    }
-
-    fn gen_constant_expr(&self, constant: Const) -> Vec<Inst> {
+        match expr.kind {
+            ExprKind::Semi(expr) => self.gen_expr(expr),
+            ExprKind::Const(constant) => self.gen_constant_expr(constant, expr.id),
+            ExprKind::If(if_expr) => self.gen_if_expr(if_expr),
+            ExprKind::For(for_expr) => self.gen_for_expr(for_expr, expr.id),
+            ExprKind::Break(break_expr) => self.gen_break_expr(break_expr, expr.id),
+            ExprKind::Continue(_) => self.gen_continue_expr(expr.id),
+            ExprKind::Return(value) => self.gen_return_expr(value),
+            ExprKind::BindDef(bind_def) => self.gen_bind_def_expr(bind_def),
+            ExprKind::BindRef(bind_ref) => self.gen_bind_ref_expr(bind_ref, expr.id, expr.span),
+            ExprKind::Assign(assign_expr) => self.gen_assign_expr(assign_expr),
+            ExprKind::Compound(compound_expr) => self.gen_compound_expr(compound_expr),
+            ExprKind::FnCall(fn_call_expr) => self.gen_fn_call_expr(fn_call_expr),
+            ExprKind::Binary(BinaryExpr {
+                op: BinaryOp::LogicalAnd | BinaryOp::LogicalOr,
+                ..
+            }) => self.gen_materialized_cond(expr),
+            ExprKind::Binary(binary_expr) => self.gen_binary_expr(binary_expr),
+            ExprKind::Unary(unary_expr) => self.gen_unary_expr(unary_expr, expr.id),
+            ExprKind::Cast(cast_expr) => self.gen_cast_expr(cast_expr, expr.id),
+            ExprKind::Error => vec![],
+            ExprKind::Function(_) => {
+                self.ctx.report(Diagnostic::error(
+                    "function literals can only be declared at the top level",
+                    expr.span,
+                ));
+
+                vec![]
+            }
+        }
+    }
+
+    fn gen_constant_expr(&self, constant: Const, id: NodeId) -> Vec<Inst> {
         match constant {
-            Const::IntegerConstant { value } => {
+            Const::Unit => vec![],
+            // Constants too large for `i64` are written as the negative number with the same
+            // bits, which the assembler takes just the same.
+            Const::IntegerConstant { value, .. } => {
                 vec![Inst::Mov {
-                    target: Arg::Reg(Reg::Eax),
-                    source: Arg::Imm(value),
+                    target: Arg::Reg(Reg::Rax, self.size_of_value(id)),
+                    source: Arg::Imm(value as i64),
+                }]
+            }
+            Const::BoolConstant { value } => {
+                vec![Inst::Mov {
+                    target: Arg::Reg(Reg::Rax, Size::Dword),
+                    source: Arg::Imm(value as i64),
                 }]
             }
         }
     }
 
+    /// Each branch leaves its value in `rax` before jumping to the exit label,
+    /// so that's where the value of the whole `if` is found.
     fn gen_if_expr(&mut self, if_expr: IfExpr) -> Vec<Inst> {
         let (first_branch_insts, mut next_label) =
             self.gen_cond_and_branch(if_expr.cond_expr, if_expr.true_branch);
@@ -183,24 +561,15 @@ impl<'ctx> CodeGen<'ctx> {
         cond_expr: &Expr,
         branch: CompoundExpr,
     ) -> (Vec<Inst>, Symbol) {
-        let mut insts = self.gen_expr(cond_expr);
-
-        insts.push(Inst::Cmp {
-            reg: Reg::Eax,
-            value: 0,
-        });
-
         let next_branch_label = self.make_label();
-        insts.push(Inst::Je {
-            label: next_branch_label,
-        });
 
+        let mut insts = self.gen_cond_jump(cond_expr, false, next_branch_label);
         insts.extend(self.gen_compound_expr(branch));
 
         (insts, next_branch_label)
     }
 
-    fn gen_for_expr(&mut self, for_expr: ForExpr) -> Vec<Inst> {
+    fn gen_for_expr(&mut self, for_expr: ForExpr, for_id: NodeId) -> Vec<Inst> {
         let mut insts = vec![];
 
         self.enter_scope();
@@ -208,20 +577,21 @@ impl<'ctx> CodeGen<'ctx> {
         let start_label = self.make_label();
         let exit_label = self.make_label();
 
-        self.set_innermost_start_label(start_label);
-        self.set_innermost_exit_label(exit_label);
+        self.labels_by_loop.insert(
+            for_id,
+            LoopLabels {
+                start: start_label,
+                exit: exit_label,
+                is_iterative: matches!(for_expr.iteration, Some(ForIteration::Iterative { .. })),
+                step: None,
+            },
+        );
 
         match for_expr.iteration {
             Some(ForIteration::Conditional { cond_expr }) => {
                 insts.push(Inst::Label { name: start_label });
 
-                insts.extend(self.gen_expr(cond_expr));
-                insts.push(Inst::Cmp {
-                    reg: Reg::Eax,
-                    value: 0,
-                });
-
-                insts.push(Inst::Je { label: exit_label });
+                insts.extend(self.gen_cond_jump(cond_expr, false, exit_label));
                 insts.extend(self.gen_compound_expr(for_expr.body));
             }
             Some(ForIteration::Iterative {
@@ -229,46 +599,121 @@ impl<'ctx> CodeGen<'ctx> {
                 start_expr,
                 end_expr,
                 range_kind,
+                step_expr,
+                is_descending,
+                ..
             }) => {
-                insts.extend(self.gen_bind_def_expr(BindDef {
-                    identifier,
-                    value: start_expr,
-                }));
+                let size = self.size_of_value(start_expr.id);
+                let is_signed = self.is_signed_value(start_expr.id);
+                let rax = Arg::Reg(Reg::Rax, size);
 
-                insts.push(Inst::Label { name: start_label });
+                insts.extend(self.gen_expr(start_expr));
 
-                let bind_ref = BindRef { identifier };
+                let bind_offset = self.allocate_stack_slot(size);
+                let bind_slot = Arg::MemOffset {
+                    base: Reg::Rbp,
+                    offset: bind_offset,
+                    size,
+                };
 
-                insts.extend(self.gen_bind_ref_expr(bind_ref));
-                // FIXME: This is specialized because I can't allocate registers at will.
-                let value = match end_expr {
-                    Expr::Const(Const::IntegerConstant { value }) => *value,
-                    _ => unimplemented!(),
+                insts.push(Inst::Mov {
+                    target: bind_slot,
+                    source: rax,
+                });
+
+                let end_value = self.const_eval.eval(end_expr);
+                let (end_insts, end) = self.gen_loop_operand(end_expr, end_value, size);
+                insts.extend(end_insts);
+
+                let (step_insts, step) = match step_expr {
+                    Some(step_expr) => {
+                        let step_value = self.const_eval.eval_step(step_expr);
+                        let (mut step_insts, step) =
+                            self.gen_loop_operand(step_expr, step_value, size);
+
+                        // A step known at compile time is already checked to be positive,
+                        // while one known only at run time that isn't makes the range
+                        // empty.
+                        if let Err(EvalError::NotConstant(_) | EvalError::LimitReached) = step_value
+                        {
+                            step_insts.push(Inst::Cmp {
+                                target: step,
+                                source: Arg::Imm(0),
+                            });
+                            step_insts.push(Inst::Jcc {
+                                cond: if is_signed { CondCode::Le } else { CondCode::E },
+                                label: exit_label,
+                            });
+                        }
+
+                        (step_insts, step)
+                    }
+                    None => (vec![], Arg::Imm(1)),
                 };
+                insts.extend(step_insts);
+
+                // The loop variable is only visible in the body, so the bounds and step
+                // still see a binding of the same name from around the loop.
+                self.insert_in_scope(identifier, bind_offset);
+
+                insts.push(Inst::Label { name: start_label });
 
+                insts.push(Inst::Mov {
+                    target: rax,
+                    source: bind_slot,
+                });
                 insts.push(Inst::Cmp {
-                    reg: Reg::Eax,
-                    value,
+                    target: rax,
+                    source: end,
+                });
+
+                let exit_cond = match (range_kind, is_descending) {
+                    (RangeKind::Inclusive, false) => CondCode::G,
+                    (RangeKind::Exclusive, false) => CondCode::Ge,
+                    (RangeKind::Inclusive, true) => CondCode::L,
+                    (RangeKind::Exclusive, true) => CondCode::Le,
+                };
+                let exit_cond = if is_signed {
+                    exit_cond
+                } else {
+                    exit_cond.as_unsigned()
+                };
+
+                insts.push(Inst::Jcc {
+                    cond: exit_cond,
+                    label: exit_label,
                 });
-                match range_kind {
-                    RangeKind::Inclusive => insts.push(Inst::Jg { label: exit_label }),
-                    RangeKind::Exclusive => insts.push(Inst::Jge { label: exit_label }),
-                }
 
                 insts.extend(self.gen_compound_expr(for_expr.body));
 
-                insts.extend(self.gen_bind_ref_expr(bind_ref));
-                insts.push(Inst::Add {
-                    target: Arg::Reg(Reg::Eax),
-                    source: Arg::Imm(1),
+                if let Some(step_label) = self.labels_by_loop[&for_id].step {
+                    insts.push(Inst::Label { name: step_label });
+                }
+
+                insts.push(Inst::Mov {
+                    target: rax,
+                    source: bind_slot,
+                });
+                insts.push(if is_descending {
+                    Inst::Sub {
+                        target: rax,
+                        source: step,
+                    }
+                } else {
+                    Inst::Add {
+                        target: rax,
+                        source: step,
+                    }
+                });
+                // The loop ends once the next value doesn't fit in the loop variable's type,
+                // rather than wrapping around and going on forever.
+                insts.push(Inst::Jcc {
+                    cond: if is_signed { CondCode::O } else { CondCode::C },
+                    label: exit_label,
                 });
-                let bind_offset = self.get_in_scope(bind_ref);
                 insts.push(Inst::Mov {
-                    target: Arg::MemOffset {
-                        base: Reg::Rbp,
-                        offset: -(bind_offset as i32),
-                    },
-                    source: Arg::Reg(Reg::Eax),
+                    target: bind_slot,
+                    source: rax,
                 });
             }
             None => {
@@ -280,52 +725,196 @@ impl<'ctx> CodeGen<'ctx> {
         insts.push(Inst::Jmp { label: start_label });
         insts.push(Inst::Label { name: exit_label });
 
+        self.labels_by_loop.remove(&for_id);
         self.exit_scope();
 
         insts
     }
 
-    fn gen_break_expr(&mut self) -> Vec<Inst> {
-        let exit_label = self.get_innermost_exit_label();
+    /// Jumps out of the loop that the `break` refers to, leaving the value of
+    /// the loop, if any, in `rax`.
+    fn gen_break_expr(&mut self, break_expr: BreakExpr, break_id: NodeId) -> Vec<Inst> {
+        let mut insts = match break_expr.value {
+            Some(value) => self.gen_expr(value),
+            None => vec![],
+        };
+
+        let target_id = self.target_loop_of(break_id);
+
+        insts.push(Inst::Jmp {
+            label: self.labels_by_loop[&target_id].exit,
+        });
+
+        insts
+    }
+
+    /// Jumps to the next iteration of the loop that the `continue` refers to,
+    /// stepping its variable first if it has one.
+    fn gen_continue_expr(&mut self, continue_id: NodeId) -> Vec<Inst> {
+        let target_id = self.target_loop_of(continue_id);
+        let loop_labels = self.labels_by_loop[&target_id];
+
+        let label = if !loop_labels.is_iterative {
+            loop_labels.start
+        } else if let Some(step_label) = loop_labels.step {
+            step_label
+        } else {
+            let step_label = self.make_label();
+
+            self.labels_by_loop
+                .get_mut(&target_id)
+                .expect("the loop is still being generated")
+                .step = Some(step_label);
+
+            step_label
+        };
+
+        vec![Inst::Jmp { label }]
+    }
+
+    /// Makes an operand for the end or step of a range, which is evaluated once
+    /// before the loop starts. Values known at compile time, as given by
+    /// `value`, are used as is, while others are kept in a stack slot of their
+    /// own.
+    fn gen_loop_operand(
+        &mut self,
+        expr: &Expr,
+        value: Result<i128, EvalError>,
+        size: Size,
+    ) -> (Vec<Inst>, Arg) {
+        match value {
+            // Instructions only take 32-bit immediates, which they sign-extend.
+            Ok(value) if size != Size::Qword || i32::try_from(value).is_ok() => {
+                return (vec![], Arg::Imm(value as i64));
+            }
+            Err(EvalError::Reported) => return (vec![], Arg::Imm(0)),
+            _ => {}
+        }
 
-        vec![Inst::Jmp { label: exit_label }]
+        let mut insts = self.gen_expr(expr);
+
+        let slot = Arg::MemOffset {
+            base: Reg::Rbp,
+            offset: self.allocate_stack_slot(size),
+            size,
+        };
+
+        insts.push(Inst::Mov {
+            target: slot,
+            source: Arg::Reg(Reg::Rax, size),
+        });
+
+        (insts, slot)
     }
 
-    fn gen_continue_expr(&mut self) -> Vec<Inst> {
-        let start_label = self.get_innermost_start_label();
+    fn gen_return_expr(&mut self, value: Option<&Expr>) -> Vec<Inst> {
+        let mut insts = match value {
+            Some(value) => self.gen_expr(value),
+            None => vec![],
+        };
+
+        let return_label = match self.return_label {
+            Some(return_label) => return_label,
+            None => {
+                let return_label = self.make_label();
+                self.return_label = Some(return_label);
+
+                return_label
+            }
+        };
+
+        insts.push(Inst::Jmp {
+            label: return_label,
+        });
 
-        vec![Inst::Jmp { label: start_label }]
+        insts
     }
 
     fn gen_bind_def_expr(&mut self, bind_def: BindDef) -> Vec<Inst> {
         let mut insts = self.gen_expr(bind_def.value);
 
-        let offset = self.insert_in_scope(bind_def);
+        // A binding of type `()` holds no value, so it doesn't need a stack slot.
+        if self.holds_no_value(bind_def.value.id) {
+            return insts;
+        }
+
+        let size = self.size_of_value(bind_def.value.id);
+        let offset = self.allocate_stack_slot(size);
+        self.insert_in_scope(bind_def.identifier, offset);
 
         insts.push(Inst::Mov {
             target: Arg::MemOffset {
                 base: Reg::Rbp,
-                // FIXME: Should not cast allocated_stack_bytes to i32.
-                offset: -(offset as i32),
+                offset,
+                size,
             },
-            source: Arg::Reg(Reg::Eax),
+            source: Arg::Reg(Reg::Rax, size),
         });
 
         insts
     }
 
-    fn gen_bind_ref_expr(&mut self, bind_ref: BindRef) -> Vec<Inst> {
-        let bind_offset = self.get_in_scope(bind_ref);
+    fn gen_bind_ref_expr(&mut self, bind_ref: BindRef, id: NodeId, span: Span) -> Vec<Inst> {
+        if let Ty::Function(_) = self.types.get(id) {
+            // FIXME: Functions have no address to take yet.
+            self.ctx.report(Diagnostic::error(
+                format!(
+                    "function `{}` can't be used as a value",
+                    self.ctx.resolve_symbol(bind_ref.identifier)
+                ),
+                span,
+            ));
+
+            return vec![];
+        }
+
+        if self.holds_no_value(id) {
+            return vec![];
+        }
+
+        let size = self.size_of_value(id);
+
+        let source = match self.constant_by_symbol.get(&bind_ref.identifier) {
+            Some(&value) if self.get_in_scope(bind_ref).is_none() => Arg::Imm(value),
+            _ => self.location_of(bind_ref.identifier, size),
+        };
 
         vec![Inst::Mov {
-            target: Arg::Reg(Reg::Eax),
-            source: Arg::MemOffset {
-                base: Reg::Rbp,
-                offset: -(bind_offset as i32),
-            },
+            target: Arg::Reg(Reg::Rax, size),
+            source,
         }]
     }
 
+    fn gen_assign_expr(&mut self, assign_expr: AssignExpr) -> Vec<Inst> {
+        let mut insts = self.gen_expr(assign_expr.value);
+
+        if self.holds_no_value(assign_expr.value.id) {
+            return insts;
+        }
+
+        let size = self.size_of_value(assign_expr.value.id);
+        let bind_slot = self.location_of(assign_expr.identifier, size);
+
+        if let Some(op) = assign_expr.op {
+            insts.push(Inst::Mov {
+                target: Arg::Reg(Reg::Rcx, size),
+                source: Arg::Reg(Reg::Rax, size),
+            });
+            insts.push(Inst::Mov {
+                target: Arg::Reg(Reg::Rax, size),
+                source: bind_slot,
+            });
+            insts.extend(self.gen_arithmetic(op, size, self.is_signed_value(assign_expr.value.id)));
+        }
+
+        insts.push(Inst::Mov {
+            target: bind_slot,
+            source: Arg::Reg(Reg::Rax, size),
+        });
+
+        insts
+    }
+
     fn gen_compound_expr(&mut self, compound_expr: CompoundExpr) -> Vec<Inst> {
         self.enter_scope();
         let insts = compound_expr
@@ -338,10 +927,428 @@ impl<'ctx> CodeGen<'ctx> {
         insts
     }
 
+    /// Evaluates the arguments from left to right, then passes them following
+    /// the System V ABI: the first six in registers, the rest pushed on the
+    /// stack in reverse order. Arguments of type `()` are evaluated but not
+    /// passed, just like their parameters.
     fn gen_fn_call_expr(&mut self, fn_call_expr: FnCallExpr) -> Vec<Inst> {
-        vec![Inst::Call {
+        let mut insts = vec![];
+        let mut argument_slots = vec![];
+
+        // FIXME: Arguments are spilled because I can't allocate registers at will.
+        for argument in fn_call_expr.arguments {
+            insts.extend(self.gen_expr(argument));
+
+            if self.holds_no_value(argument.id) {
+                continue;
+            }
+
+            let size = self.size_of_value(argument.id);
+            let argument_slot = Arg::MemOffset {
+                base: Reg::Rbp,
+                offset: self.allocate_stack_slot(size),
+                size,
+            };
+
+            insts.push(Inst::Mov {
+                target: argument_slot,
+                source: Arg::Reg(Reg::Rax, size),
+            });
+
+            argument_slots.push((argument_slot, size));
+        }
+
+        let register_arg_count = argument_slots.len().min(CodeGen::ARG_REGS.len());
+        let (register_arg_slots, stack_arg_slots) = argument_slots.split_at(register_arg_count);
+
+        // Each stack argument takes up an eightbyte, of which the callee only reads the
+        // bytes of its type. They go at the bottom of the frame, where `rsp` points to
+        // at the call, with the first one right at `rsp`.
+        for (stack_arg_idx, &(stack_arg_slot, size)) in stack_arg_slots.iter().enumerate() {
+            insts.push(Inst::Mov {
+                target: Arg::Reg(Reg::Rax, size),
+                source: stack_arg_slot,
+            });
+            // FIXME: Should not cast the stack argument index to i32.
+            insts.push(Inst::Mov {
+                target: Arg::MemOffset {
+                    base: Reg::Rsp,
+                    offset: 8 * stack_arg_idx as i32,
+                    size,
+                },
+                source: Arg::Reg(Reg::Rax, size),
+            });
+        }
+
+        self.outgoing_arg_bytes = self.outgoing_arg_bytes.max(8 * stack_arg_slots.len());
+
+        for (&arg_reg, &(register_arg_slot, size)) in
+            CodeGen::ARG_REGS.iter().zip(register_arg_slots)
+        {
+            insts.push(Inst::Mov {
+                target: Arg::Reg(arg_reg, size),
+                source: register_arg_slot,
+            });
+        }
+
+        insts.push(Inst::Call {
             label: fn_call_expr.identifier,
-        }]
+        });
+
+        insts
+    }
+
+    /// Jumps to `label` if the condition evaluates to `jump_if`, and falls
+    /// through otherwise. Comparisons branch on the flags they set, and
+    /// `&&`, `||` and `!` become jumps, so a condition's value only ends up
+    /// in a register if it comes from elsewhere.
+    fn gen_cond_jump(&mut self, cond_expr: &Expr, jump_if: bool, label: Symbol) -> Vec<Inst> {
+        match cond_expr.kind {
+            ExprKind::Const(Const::BoolConstant { value }) => {
+                if value == jump_if {
+                    vec![Inst::Jmp { label }]
+                } else {
+                    vec![]
+                }
+            }
+            ExprKind::Unary(UnaryExpr {
+                op: UnaryOp::Not,
+                operand,
+            }) => self.gen_cond_jump(operand, !jump_if, label),
+            ExprKind::Binary(BinaryExpr {
+                op: op @ (BinaryOp::LogicalAnd | BinaryOp::LogicalOr),
+                lhs,
+                rhs,
+            }) => {
+                // The value that the left operand gives to the whole expression on its own:
+                // `false` for `&&`, and `true` for `||`.
+                let short_circuit_value = op == BinaryOp::LogicalOr;
+
+                if short_circuit_value == jump_if {
+                    let mut insts = self.gen_cond_jump(lhs, jump_if, label);
+                    insts.extend(self.gen_cond_jump(rhs, jump_if, label));
+
+                    insts
+                } else {
+                    let skip_label = self.make_label();
+
+                    let mut insts = self.gen_cond_jump(lhs, short_circuit_value, skip_label);
+                    insts.extend(self.gen_cond_jump(rhs, jump_if, label));
+                    insts.push(Inst::Label { name: skip_label });
+
+                    insts
+                }
+            }
+            ExprKind::Binary(binary_expr) if comparison_cond_of(binary_expr.op).is_some() => {
+                let cond = self.comparison_cond(binary_expr);
+                let size = self.size_of_value(binary_expr.lhs.id);
+
+                let mut insts = self.gen_binary_operands(binary_expr);
+                insts.push(Inst::Cmp {
+                    target: Arg::Reg(Reg::Rax, size),
+                    source: Arg::Reg(Reg::Rcx, size),
+                });
+                insts.push(Inst::Jcc {
+                    cond: if jump_if { cond } else { cond.negated() },
+                    label,
+                });
+
+                insts
+            }
+            _ => {
+                let mut insts = self.gen_expr(cond_expr);
+                insts.push(Inst::Cmp {
+                    target: Arg::Reg(Reg::Rax, Size::Dword),
+                    source: Arg::Imm(0),
+                });
+                insts.push(Inst::Jcc {
+                    cond: if jump_if { CondCode::Ne } else { CondCode::E },
+                    label,
+                });
+
+                insts
+            }
+        }
+    }
+
+    /// Evaluates a condition made of jumps, such as `&&` and `||`, as 0 or 1 in
+    /// `eax`.
+    fn gen_materialized_cond(&mut self, cond_expr: &Expr) -> Vec<Inst> {
+        let false_label = self.make_label();
+        let exit_label = self.make_label();
+
+        let mut insts = self.gen_cond_jump(cond_expr, false, false_label);
+
+        insts.push(Inst::Mov {
+            target: Arg::Reg(Reg::Rax, Size::Dword),
+            source: Arg::Imm(1),
+        });
+        insts.push(Inst::Jmp { label: exit_label });
+        insts.push(Inst::Label { name: false_label });
+        insts.push(Inst::Mov {
+            target: Arg::Reg(Reg::Rax, Size::Dword),
+            source: Arg::Imm(0),
+        });
+        insts.push(Inst::Label { name: exit_label });
+
+        insts
+    }
+
+    /// Evaluates the operands of a binary expression, leaving the left one in
+    /// `rax` and the right one in `rcx`, sized to their type.
+    fn gen_binary_operands(&mut self, binary_expr: BinaryExpr) -> Vec<Inst> {
+        let size = self.size_of_value(binary_expr.lhs.id);
+
+        let mut insts = self.gen_expr(binary_expr.lhs);
+
+        // FIXME: The left operand is spilled because I can't allocate registers at
+        // will.
+        let lhs_slot = Arg::MemOffset {
+            base: Reg::Rbp,
+            offset: self.allocate_stack_slot(size),
+            size,
+        };
+
+        insts.push(Inst::Mov {
+            target: lhs_slot,
+            source: Arg::Reg(Reg::Rax, size),
+        });
+
+        insts.extend(self.gen_expr(binary_expr.rhs));
+
+        insts.push(Inst::Mov {
+            target: Arg::Reg(Reg::Rcx, size),
+            source: Arg::Reg(Reg::Rax, size),
+        });
+        insts.push(Inst::Mov {
+            target: Arg::Reg(Reg::Rax, size),
+            source: lhs_slot,
+        });
+
+        insts
+    }
+
+    fn gen_binary_expr(&mut self, binary_expr: BinaryExpr) -> Vec<Inst> {
+        let size = self.size_of_value(binary_expr.lhs.id);
+        let is_signed = self.is_signed_value(binary_expr.lhs.id);
+
+        let mut insts = self.gen_binary_operands(binary_expr);
+
+        match binary_expr.op {
+            BinaryOp::Less
+            | BinaryOp::Greater
+            | BinaryOp::LessEqual
+            | BinaryOp::GreaterEqual
+            | BinaryOp::Equal
+            | BinaryOp::NotEqual => {
+                insts.push(Inst::Cmp {
+                    target: Arg::Reg(Reg::Rax, size),
+                    source: Arg::Reg(Reg::Rcx, size),
+                });
+                insts.extend(self.gen_set_bool(self.comparison_cond(binary_expr)));
+            }
+            BinaryOp::LogicalAnd | BinaryOp::LogicalOr => {
+                unreachable!("logical operators are generated as conditions")
+            }
+            op => insts.extend(self.gen_arithmetic(op, size, is_signed)),
+        }
+
+        insts
+    }
+
+    /// Applies an arithmetic operator to `rax` and `rcx`, both of the given
+    /// size, leaving the result in `rax`.
+    fn gen_arithmetic(&self, op: BinaryOp, size: Size, is_signed: bool) -> Vec<Inst> {
+        let mut insts = vec![];
+
+        let rax = Arg::Reg(Reg::Rax, size);
+        let rcx = Arg::Reg(Reg::Rcx, size);
+
+        match op {
+            BinaryOp::Add => insts.push(Inst::Add {
+                target: rax,
+                source: rcx,
+            }),
+            BinaryOp::Sub => insts.push(Inst::Sub {
+                target: rax,
+                source: rcx,
+            }),
+            // There's no 8-bit form of `imul`, but the low byte of the product is the same
+            // whatever the size of the registers.
+            BinaryOp::Mul => {
+                let mul_size = size.max(Size::Word);
+
+                insts.push(Inst::Imul {
+                    target: Arg::Reg(Reg::Rax, mul_size),
+                    source: Arg::Reg(Reg::Rcx, mul_size),
+                });
+            }
+            BinaryOp::Div => insts.extend(self.gen_division(size, is_signed)),
+            BinaryOp::Shl if is_signed => insts.push(Inst::Sal {
+                target: rax,
+                source: Arg::Reg(Reg::Rcx, Size::Byte),
+            }),
+            BinaryOp::Shl => insts.push(Inst::Shl {
+                target: rax,
+                source: Arg::Reg(Reg::Rcx, Size::Byte),
+            }),
+            BinaryOp::Shr if is_signed => insts.push(Inst::Sar {
+                target: rax,
+                source: Arg::Reg(Reg::Rcx, Size::Byte),
+            }),
+            BinaryOp::Shr => insts.push(Inst::Shr {
+                target: rax,
+                source: Arg::Reg(Reg::Rcx, Size::Byte),
+            }),
+            _ => unreachable!("only arithmetic operators are applied to integers this way"),
+        }
+
+        insts
+    }
+
+    /// Divides `rax` by `rcx`, both of the given size, leaving the quotient in
+    /// `rax`. Bytes and words are extended to doublewords first, so that
+    /// the dividend fits in `edx:eax`.
+    fn gen_division(&self, size: Size, is_signed: bool) -> Vec<Inst> {
+        let mut insts = vec![];
+        let division_size = size.max(Size::Dword);
+
+        if size < division_size {
+            for reg in [Reg::Rax, Reg::Rcx] {
+                insts.push(CodeGen::gen_extension(
+                    Arg::Reg(reg, division_size),
+                    Arg::Reg(reg, size),
+                    is_signed,
+                ));
+            }
+        }
+
+        let divisor = Arg::Reg(Reg::Rcx, division_size);
+
+        if is_signed {
+            if division_size == Size::Qword {
+                insts.push(Inst::Cqo);
+            } else {
+                insts.push(Inst::Cdq);
+            }
+
+            insts.push(Inst::Idiv { source: divisor });
+        } else {
+            insts.push(Inst::Xor {
+                target: Arg::Reg(Reg::Rdx, Size::Dword),
+                source: Arg::Reg(Reg::Rdx, Size::Dword),
+            });
+            insts.push(Inst::Div { source: divisor });
+        }
+
+        insts
+    }
+
+    fn gen_unary_expr(&mut self, unary_expr: UnaryExpr, id: NodeId) -> Vec<Inst> {
+        // A negated constant is moved with its sign instead, as the minimum value of a
+        // signed type has no positive counterpart to negate.
+        if let (UnaryOp::Neg, ExprKind::Const(Const::IntegerConstant { value, .. })) =
+            (unary_expr.op, unary_expr.operand.kind)
+        {
+            return vec![Inst::Mov {
+                target: Arg::Reg(Reg::Rax, self.size_of_value(id)),
+                source: Arg::Imm(-i128::from(value) as i64),
+            }];
+        }
+
+        let mut insts = self.gen_expr(unary_expr.operand);
+
+        match unary_expr.op {
+            UnaryOp::Neg => insts.push(Inst::Neg {
+                target: Arg::Reg(Reg::Rax, self.size_of_value(id)),
+            }),
+            UnaryOp::Not => {
+                insts.push(Inst::Cmp {
+                    target: Arg::Reg(Reg::Rax, Size::Dword),
+                    source: Arg::Imm(0),
+                });
+                insts.extend(self.gen_set_bool(CondCode::E));
+            }
+        }
+
+        insts
+    }
+
+    /// Converts the value in `rax` to the cast's type. Narrowing just leaves
+    /// the upper bits behind, while widening fills them according to the
+    /// signedness of the operand's type.
+    fn gen_cast_expr(&mut self, cast_expr: CastExpr, id: NodeId) -> Vec<Inst> {
+        let mut insts = self.gen_expr(cast_expr.operand);
+
+        let operand_size = self.size_of_value(cast_expr.operand.id);
+        let size = self.size_of_value(id);
+
+        if size > operand_size {
+            insts.push(CodeGen::gen_extension(
+                Arg::Reg(Reg::Rax, size),
+                Arg::Reg(Reg::Rax, operand_size),
+                self.is_signed_value(cast_expr.operand.id),
+            ));
+        }
+
+        insts
+    }
+
+    /// Sign or zero extends `source` into the wider `target`.
+    fn gen_extension(target: Arg, source: Arg, is_signed: bool) -> Inst {
+        match (target, source, is_signed) {
+            (_, _, true) => Inst::Movsx { target, source },
+            // Writing a doubleword register already clears the upper half of its quadword.
+            (Arg::Reg(target_reg, _), Arg::Reg(_, Size::Dword), false) => Inst::Mov {
+                target: Arg::Reg(target_reg, Size::Dword),
+                source,
+            },
+            (_, _, false) => Inst::Movzx { target, source },
+        }
+    }
+
+    /// Materializes the flags of a preceding `cmp` as 0 or 1 in `eax`.
+    fn gen_set_bool(&self, cond: CondCode) -> Vec<Inst> {
+        vec![
+            Inst::Set {
+                cond,
+                target: Arg::Reg(Reg::Rax, Size::Byte),
+            },
+            Inst::Movzx {
+                target: Arg::Reg(Reg::Rax, Size::Dword),
+                source: Arg::Reg(Reg::Rax, Size::Byte),
+            },
+        ]
+    }
+
+    /// The condition code under which a comparison holds, which depends on
+    /// whether its operands are signed.
+    fn comparison_cond(&self, binary_expr: BinaryExpr) -> CondCode {
+        let cond = comparison_cond_of(binary_expr.op).expect("the operator is a comparison");
+
+        if self.is_signed_value(binary_expr.lhs.id) {
+            cond
+        } else {
+            cond.as_unsigned()
+        }
+    }
+
+    /// The size of the registers and memory that hold the expression's value.
+    fn size_of_value(&self, node_id: NodeId) -> Size {
+        match self.types.get(node_id) {
+            Ty::Value(ty) => Size::of_type(ty).unwrap_or(Size::Dword),
+            _ => Size::Dword,
+        }
+    }
+
+    fn is_signed_value(&self, node_id: NodeId) -> bool {
+        matches!(self.types.get(node_id), Ty::Value(Type::Int(int_type)) if int_type.is_signed())
+    }
+
+    /// Tells whether the expression has a type with no values to store, like
+    /// `()`.
+    fn holds_no_value(&self, node_id: NodeId) -> bool {
+        matches!(self.types.get(node_id), Ty::Value(Type::Unit) | Ty::Never)
     }
 
     fn make_label(&mut self) -> Symbol {
@@ -360,6 +1367,7 @@ impl<'ctx> CodeGen<'ctx> {
 
         if self.scope_stack.is_empty() {
             self.allocated_stack_bytes = 0;
+            self.outgoing_arg_bytes = 0;
         }
     }
 
@@ -367,19 +1375,25 @@ impl<'ctx> CodeGen<'ctx> {
         self.scope_stack.last_mut().unwrap()
     }
 
-    fn insert_in_scope(&mut self, bind_def: BindDef) -> usize {
-        let bumped_allocated_stack_bytes = self.allocated_stack_bytes + 4;
-
+    fn insert_in_scope(&mut self, identifier: Symbol, offset: i32) {
         self.get_this_scope_mut()
             .memory_offset_by_symbol
-            .insert(bind_def.identifier, bumped_allocated_stack_bytes);
+            .insert(identifier, offset);
+    }
 
-        self.allocated_stack_bytes = bumped_allocated_stack_bytes;
+    /// Reserves a slot of the given size in the current stack frame, aligned to
+    /// its size, and returns its offset from `rbp`.
+    fn allocate_stack_slot(&mut self, size: Size) -> i32 {
+        let size_in_bytes = size.in_bytes();
 
-        self.allocated_stack_bytes
+        self.allocated_stack_bytes =
+            (self.allocated_stack_bytes + size_in_bytes).next_multiple_of(size_in_bytes);
+
+        // FIXME: Should not cast allocated_stack_bytes to i32.
+        -(self.allocated_stack_bytes as i32)
     }
 
-    fn get_in_scope(&self, bind_ref: BindRef) -> usize {
+    fn get_in_scope(&self, bind_ref: BindRef) -> Option<i32> {
         self.find_in_scope(|scope| {
             scope
                 .memory_offset_by_symbol
@@ -388,31 +1402,47 @@ impl<'ctx> CodeGen<'ctx> {
         })
     }
 
-    fn find_in_scope<R: Clone, F: Fn(&Scope) -> Option<R>>(&self, f: F) -> R {
-        for scope in self.scope_stack.iter().rev() {
-            if let Some(r) = f(scope) {
-                return r;
-            }
+    /// Where the value of a binding or global is kept. Names that aren't bound
+    /// in any scope are globals, as names are resolved before code generation.
+    fn location_of(&self, identifier: Symbol, size: Size) -> Arg {
+        match self.get_in_scope(BindRef { identifier }) {
+            Some(offset) => Arg::MemOffset {
+                base: Reg::Rbp,
+                offset,
+                size,
+            },
+            None => Arg::Global {
+                name: identifier,
+                size,
+            },
         }
-
-        unreachable!("scope does not exist")
-    }
-
-    fn get_innermost_start_label(&self) -> Symbol {
-        self.find_in_scope(|scope| scope.innermost_start_label)
     }
 
-    fn set_innermost_start_label(&mut self, start_label: Symbol) {
-        self.get_this_scope_mut().innermost_start_label = Some(start_label)
+    fn find_in_scope<R: Clone, F: Fn(&Scope) -> Option<R>>(&self, f: F) -> Option<R> {
+        self.scope_stack.iter().rev().find_map(f)
     }
 
-    fn get_innermost_exit_label(&self) -> Symbol {
-        self.find_in_scope(|scope| scope.innermost_exit_label)
+    /// The id of the loop that a `break` or `continue` refers to.
+    fn target_loop_of(&self, node_id: NodeId) -> NodeId {
+        self.loop_targets
+            .get(node_id)
+            .expect("`break` and `continue` are resolved to a loop before code generation")
     }
+}
 
-    fn set_innermost_exit_label(&mut self, exit_label: Symbol) {
-        self.get_this_scope_mut().innermost_exit_label = Some(exit_label)
-    }
+/// The condition code under which a comparison operator holds, if `op` is one.
+fn comparison_cond_of(op: BinaryOp) -> Option<CondCode> {
+    let cond = match op {
+        BinaryOp::Less => CondCode::L,
+        BinaryOp::Greater => CondCode::G,
+        BinaryOp::LessEqual => CondCode::Le,
+        BinaryOp::GreaterEqual => CondCode::Ge,
+        BinaryOp::Equal => CondCode::E,
+        BinaryOp::NotEqual => CondCode::Ne,
+        _ => return None,
+    };
+
+    Some(cond)
 }
 
 pub(crate) struct X86Program<'ctx> {
@@ -422,35 +1452,253 @@ pub(crate) struct X86Program<'ctx> {
 
 #[derive(Clone, Copy)]
 enum Inst {
-    Label { name: Symbol },
-    Mov { target: Arg, source: Arg },
-    Cmp { reg: Reg, value: i32 },
-    Je { label: Symbol },
-    Jg { label: Symbol },
-    Jge { label: Symbol },
-    Jmp { label: Symbol },
+    Label {
+        name: Symbol,
+    },
+    Mov {
+        target: Arg,
+        source: Arg,
+    },
+    Cmp {
+        target: Arg,
+        source: Arg,
+    },
+    Jcc {
+        cond: CondCode,
+        label: Symbol,
+    },
+    Jmp {
+        label: Symbol,
+    },
     Ret,
-    Push { source: Reg },
-    Pop { target: Reg },
-    Sub { target: Arg, source: Arg },
-    Add { target: Arg, source: Arg },
-    Call { label: Symbol },
+    Push {
+        source: Reg,
+    },
+    Pop {
+        target: Reg,
+    },
+    Sub {
+        target: Arg,
+        source: Arg,
+    },
+    Add {
+        target: Arg,
+        source: Arg,
+    },
+    Imul {
+        target: Arg,
+        source: Arg,
+    },
+    Cdq,
+    Cqo,
+    Idiv {
+        source: Arg,
+    },
+    Div {
+        source: Arg,
+    },
+    Xor {
+        target: Arg,
+        source: Arg,
+    },
+    Sal {
+        target: Arg,
+        source: Arg,
+    },
+    Sar {
+        target: Arg,
+        source: Arg,
+    },
+    Shl {
+        target: Arg,
+        source: Arg,
+    },
+    Shr {
+        target: Arg,
+        source: Arg,
+    },
+    Neg {
+        target: Arg,
+    },
+    Set {
+        cond: CondCode,
+        target: Arg,
+    },
+    Movsx {
+        target: Arg,
+        source: Arg,
+    },
+    Movzx {
+        target: Arg,
+        source: Arg,
+    },
+    Call {
+        label: Symbol,
+    },
+    Syscall,
+    /// Switches the assembler to the Intel syntax, without `%` before register
+    /// names.
+    IntelSyntax,
+    /// Makes the label visible to the linker.
+    Globl {
+        name: Symbol,
+    },
+    Section {
+        section: Section,
+    },
+    /// Aligns the next data to a multiple of `bytes`.
+    Align {
+        bytes: usize,
+    },
+    /// Data of the given size, with the given value.
+    Data {
+        size: Size,
+        value: i64,
+    },
+    /// Data of the given size, all zeros.
+    Zero {
+        bytes: usize,
+    },
+}
+
+/// A section of the object file, where the directives and instructions that
+/// follow go.
+#[derive(Clone, Copy)]
+enum Section {
+    /// Code.
+    Text,
+    /// Globals with an initial value.
+    Data,
+    /// Globals that start out as zero, which take no room in the object file.
+    Bss,
+    /// Tells the linker that the stack needn't be executable.
+    NoteGnuStack,
+}
+
+#[derive(Clone, Copy)]
+enum CondCode {
+    E,
+    Ne,
+    L,
+    G,
+    Le,
+    Ge,
+    B,
+    A,
+    Be,
+    Ae,
+    /// Overflow, of a signed operation.
+    O,
+    No,
+    /// Carry, or borrow, of an unsigned operation.
+    C,
+    Nc,
+}
+
+impl CondCode {
+    /// The condition code that holds exactly when this one doesn't.
+    fn negated(self) -> CondCode {
+        match self {
+            CondCode::E => CondCode::Ne,
+            CondCode::Ne => CondCode::E,
+            CondCode::L => CondCode::Ge,
+            CondCode::G => CondCode::Le,
+            CondCode::Le => CondCode::G,
+            CondCode::Ge => CondCode::L,
+            CondCode::B => CondCode::Ae,
+            CondCode::A => CondCode::Be,
+            CondCode::Be => CondCode::A,
+            CondCode::Ae => CondCode::B,
+            CondCode::O => CondCode::No,
+            CondCode::No => CondCode::O,
+            CondCode::C => CondCode::Nc,
+            CondCode::Nc => CondCode::C,
+        }
+    }
+
+    /// The condition code that compares unsigned operands the way this one
+    /// compares signed ones.
+    fn as_unsigned(self) -> CondCode {
+        match self {
+            CondCode::L => CondCode::B,
+            CondCode::G => CondCode::A,
+            CondCode::Le => CondCode::Be,
+            CondCode::Ge => CondCode::Ae,
+            cond => cond,
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
 enum Arg {
-    Imm(i32),
-    Reg(Reg),
-    MemOffset { base: Reg, offset: i32 },
+    Imm(i64),
+    Reg(Reg, Size),
+    MemOffset {
+        base: Reg,
+        offset: i32,
+        size: Size,
+    },
+    /// A global, addressed relative to the instruction pointer.
+    Global {
+        name: Symbol,
+        size: Size,
+    },
 }
 
+/// A general purpose register, which is accessed as a whole or through its
+/// lower bytes, depending on the size of the operand.
 #[derive(Clone, Copy)]
 enum Reg {
-    Eax,
+    Rax,
+    Rcx,
+    Rdx,
+    Rsi,
+    Rdi,
+    R8,
+    R9,
     Rbp,
     Rsp,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Size {
+    Byte,
+    Word,
+    Dword,
+    Qword,
+}
+
+impl Size {
+    /// The size of a value of type `ty`, or `None` if it holds no value, like
+    /// `()`. A `bool` is kept as a doubleword 0 or 1, which is what
+    /// `gen_set_bool` produces.
+    fn of_type(ty: Type) -> Option<Size> {
+        match ty {
+            Type::Unit => None,
+            Type::Bool => Some(Size::Dword),
+            Type::Int(int_type) => Some(Size::of_int_type(int_type)),
+        }
+    }
+
+    fn of_int_type(int_type: IntType) -> Size {
+        match int_type.size_in_bytes() {
+            1 => Size::Byte,
+            2 => Size::Word,
+            4 => Size::Dword,
+            _ => Size::Qword,
+        }
+    }
+
+    fn in_bytes(self) -> usize {
+        match self {
+            Size::Byte => 1,
+            Size::Word => 2,
+            Size::Dword => 4,
+            Size::Qword => 8,
+        }
+    }
+}
+
 impl fmt::Display for X86Program<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for inst in &self.instructions {
@@ -478,46 +1726,187 @@ impl fmt::Display for CtxInst<'_> {
 
         match self.inst {
             Inst::Label { name } => write!(f, "{}:", self.ctx.resolve_symbol(name)),
-            Inst::Mov { target, source } => write!(f, "mov {}, {}", target, source),
-            Inst::Cmp { reg, value } => write!(f, "cmp {}, {}", reg, value),
-            Inst::Je { label } => write!(f, "je {}", self.ctx.resolve_symbol(label)),
-            Inst::Jg { label } => write!(f, "jg {}", self.ctx.resolve_symbol(label)),
-            Inst::Jge { label } => write!(f, "jge {}", self.ctx.resolve_symbol(label)),
+            Inst::Mov { target, source } => {
+                write!(f, "mov {}, {}", self.arg(target), self.arg(source))
+            }
+            Inst::Cmp { target, source } => {
+                write!(f, "cmp {}, {}", self.arg(target), self.arg(source))
+            }
+            Inst::Jcc { cond, label } => {
+                write!(f, "j{} {}", cond, self.ctx.resolve_symbol(label))
+            }
             Inst::Jmp { label } => write!(f, "jmp {}", self.ctx.resolve_symbol(label)),
             Inst::Ret => write!(f, "ret"),
             Inst::Push { source } => write!(f, "push {}", source),
             Inst::Pop { target } => write!(f, "pop {}", target),
-            Inst::Sub { target, source } => write!(f, "sub {}, {}", target, source),
-            Inst::Add { target, source } => write!(f, "add {}, {}", target, source),
+            Inst::Sub { target, source } => {
+                write!(f, "sub {}, {}", self.arg(target), self.arg(source))
+            }
+            Inst::Add { target, source } => {
+                write!(f, "add {}, {}", self.arg(target), self.arg(source))
+            }
+            Inst::Imul { target, source } => {
+                write!(f, "imul {}, {}", self.arg(target), self.arg(source))
+            }
+            Inst::Cdq => write!(f, "cdq"),
+            Inst::Cqo => write!(f, "cqo"),
+            Inst::Idiv { source } => write!(f, "idiv {}", self.arg(source)),
+            Inst::Div { source } => write!(f, "div {}", self.arg(source)),
+            Inst::Xor { target, source } => {
+                write!(f, "xor {}, {}", self.arg(target), self.arg(source))
+            }
+            Inst::Sal { target, source } => {
+                write!(f, "sal {}, {}", self.arg(target), self.arg(source))
+            }
+            Inst::Sar { target, source } => {
+                write!(f, "sar {}, {}", self.arg(target), self.arg(source))
+            }
+            Inst::Shl { target, source } => {
+                write!(f, "shl {}, {}", self.arg(target), self.arg(source))
+            }
+            Inst::Shr { target, source } => {
+                write!(f, "shr {}, {}", self.arg(target), self.arg(source))
+            }
+            Inst::Neg { target } => write!(f, "neg {}", self.arg(target)),
+            Inst::Set { cond, target } => write!(f, "set{} {}", cond, self.arg(target)),
+            // Sign extending a doubleword has a mnemonic of its own.
+            Inst::Movsx {
+                target,
+                source: source @ Arg::Reg(_, Size::Dword),
+            } => write!(f, "movsxd {}, {}", self.arg(target), self.arg(source)),
+            Inst::Movsx { target, source } => {
+                write!(f, "movsx {}, {}", self.arg(target), self.arg(source))
+            }
+            Inst::Movzx { target, source } => {
+                write!(f, "movzx {}, {}", self.arg(target), self.arg(source))
+            }
             Inst::Call { label } => write!(f, "call {}", self.ctx.resolve_symbol(label)),
+            Inst::Syscall => write!(f, "syscall"),
+            Inst::IntelSyntax => write!(f, ".intel_syntax noprefix"),
+            Inst::Globl { name } => write!(f, ".globl {}", self.ctx.resolve_symbol(name)),
+            Inst::Section {
+                section: Section::Text,
+            } => write!(f, ".text"),
+            Inst::Section {
+                section: Section::Data,
+            } => write!(f, ".data"),
+            Inst::Section {
+                section: Section::Bss,
+            } => write!(f, ".bss"),
+            Inst::Section {
+                section: Section::NoteGnuStack,
+            } => write!(f, ".section .note.GNU-stack,\"\",@progbits"),
+            Inst::Align { bytes } => write!(f, ".align {}", bytes),
+            Inst::Data { size, value } => {
+                let directive = match size {
+                    Size::Byte => ".byte",
+                    Size::Word => ".short",
+                    Size::Dword => ".long",
+                    Size::Qword => ".quad",
+                };
+
+                write!(f, "{} {}", directive, value)
+            }
+            Inst::Zero { bytes } => write!(f, ".zero {}", bytes),
         }
     }
 }
 
-impl fmt::Display for Arg {
+impl CtxInst<'_> {
+    fn arg(&self, arg: Arg) -> CtxArg<'_> {
+        CtxArg { ctx: self.ctx, arg }
+    }
+}
+
+struct CtxArg<'ctx> {
+    ctx: &'ctx CompilerContext,
+    arg: Arg,
+}
+
+impl fmt::Display for CtxArg<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
+        match self.arg {
             Arg::Imm(value) => write!(f, "{}", value),
-            Arg::Reg(reg) => write!(f, "{}", reg),
-            Arg::MemOffset { base, offset } => {
+            Arg::Reg(reg, size) => write!(f, "{}", reg.name(size)),
+            Arg::MemOffset {
+                base,
+                offset: 0,
+                size,
+            } => write!(f, "{} PTR [{}]", size, base),
+            Arg::MemOffset { base, offset, size } => {
                 write!(
                     f,
-                    "DWORD PTR [{base}{sign}{offset}]",
+                    "{size} PTR [{base}{sign}{offset}]",
+                    size = size,
                     base = base,
-                    sign = if *offset >= 0 { "+" } else { "" },
+                    sign = if offset >= 0 { "+" } else { "" },
                     offset = offset
                 )
             }
+            Arg::Global { name, size } => {
+                write!(f, "{} PTR {}[rip]", size, self.ctx.resolve_symbol(name))
+            }
+        }
+    }
+}
+
+impl Reg {
+    fn name(self, size: Size) -> &'static str {
+        let [byte, word, dword, qword] = match self {
+            Reg::Rax => ["al", "ax", "eax", "rax"],
+            Reg::Rcx => ["cl", "cx", "ecx", "rcx"],
+            Reg::Rdx => ["dl", "dx", "edx", "rdx"],
+            Reg::Rsi => ["sil", "si", "esi", "rsi"],
+            Reg::Rdi => ["dil", "di", "edi", "rdi"],
+            Reg::R8 => ["r8b", "r8w", "r8d", "r8"],
+            Reg::R9 => ["r9b", "r9w", "r9d", "r9"],
+            Reg::Rbp => ["bpl", "bp", "ebp", "rbp"],
+            Reg::Rsp => ["spl", "sp", "esp", "rsp"],
+        };
+
+        match size {
+            Size::Byte => byte,
+            Size::Word => word,
+            Size::Dword => dword,
+            Size::Qword => qword,
         }
     }
 }
 
 impl fmt::Display for Reg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name(Size::Qword))
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Size::Byte => write!(f, "BYTE"),
+            Size::Word => write!(f, "WORD"),
+            Size::Dword => write!(f, "DWORD"),
+            Size::Qword => write!(f, "QWORD"),
+        }
+    }
+}
+
+impl fmt::Display for CondCode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Reg::Eax => write!(f, "eax"),
-            Reg::Rbp => write!(f, "rbp"),
-            Reg::Rsp => write!(f, "rsp"),
+            CondCode::E => write!(f, "e"),
+            CondCode::Ne => write!(f, "ne"),
+            CondCode::L => write!(f, "l"),
+            CondCode::G => write!(f, "g"),
+            CondCode::Le => write!(f, "le"),
+            CondCode::Ge => write!(f, "ge"),
+            CondCode::B => write!(f, "b"),
+            CondCode::A => write!(f, "a"),
+            CondCode::Be => write!(f, "be"),
+            CondCode::Ae => write!(f, "ae"),
+            CondCode::O => write!(f, "o"),
+            CondCode::No => write!(f, "no"),
+            CondCode::C => write!(f, "c"),
+            CondCode::Nc => write!(f, "nc"),
         }
     }
 }
@@ -3,26 +3,40 @@ use std::cell::RefCell;
 use bumpalo::Bump;
 
 use crate::ast::{Decl, ElseIfBranch, Expr, Param};
+use crate::diagnostic::Diagnostic;
 use crate::interner::{StringInterner, Symbol};
+use crate::scanner::{BytePos, Span};
 
 pub(crate) struct CompilerContext {
     source_code: String,
+    /// Byte position at which each line of the source code starts.
+    line_starts: Vec<BytePos>,
     string_interner: RefCell<StringInterner>,
+    diagnostics: RefCell<Vec<Diagnostic>>,
     exprs: Bump,
     else_if_branches: Bump,
     params: Bump,
     decls: Bump,
+    spans: Bump,
 }
 
 impl<'ctx> CompilerContext {
     pub(crate) fn new(source_code: String) -> CompilerContext {
+        let line_starts = std::iter::once(0)
+            .chain(source_code.match_indices('\n').map(|(idx, _)| idx + 1))
+            .map(BytePos)
+            .collect();
+
         CompilerContext {
             source_code,
+            line_starts,
             string_interner: Default::default(),
+            diagnostics: Default::default(),
             exprs: Default::default(),
             else_if_branches: Default::default(),
             params: Default::default(),
             decls: Default::default(),
+            spans: Default::default(),
         }
     }
 
@@ -30,6 +44,32 @@ impl<'ctx> CompilerContext {
         &self.source_code
     }
 
+    /// Finds the line and column of a byte position, both starting at 1.
+    /// Columns count characters rather than bytes.
+    pub(crate) fn get_location(&'ctx self, pos: BytePos) -> Location {
+        let line_idx = self
+            .line_starts
+            .partition_point(|&line_start| line_start <= pos)
+            - 1;
+        let line_start = self.line_starts[line_idx];
+
+        Location {
+            line: line_idx + 1,
+            column: self.source_code[line_start.0..pos.0].chars().count() + 1,
+        }
+    }
+
+    /// Returns the text of a line, starting at 1, without its line break.
+    pub(crate) fn get_line_text(&'ctx self, line: usize) -> &'ctx str {
+        let line_start = self.line_starts[line - 1].0;
+        let line_end = self
+            .line_starts
+            .get(line)
+            .map_or(self.source_code.len(), |next_line_start| next_line_start.0);
+
+        self.source_code[line_start..line_end].trim_end_matches(['\n', '\r'])
+    }
+
     pub(crate) fn get_or_intern_str(&'ctx self, string: &str) -> Symbol {
         self.string_interner.borrow_mut().get_or_intern(string)
     }
@@ -38,6 +78,26 @@ impl<'ctx> CompilerContext {
         self.string_interner.borrow().resolve(symbol)
     }
 
+    pub(crate) fn report(&'ctx self, diagnostic: Diagnostic) {
+        self.diagnostics.borrow_mut().push(diagnostic);
+    }
+
+    pub(crate) fn error_count(&'ctx self) -> usize {
+        self.diagnostics
+            .borrow()
+            .iter()
+            .filter(|diagnostic| diagnostic.is_error())
+            .count()
+    }
+
+    pub(crate) fn has_errors(&'ctx self) -> bool {
+        self.error_count() != 0
+    }
+
+    pub(crate) fn take_diagnostics(&'ctx self) -> Vec<Diagnostic> {
+        self.diagnostics.take()
+    }
+
     pub(crate) fn alloc_slice_of_decl<'a>(
         &'ctx self,
         decls: &'a [Decl<'ctx>],
@@ -63,10 +123,17 @@ impl<'ctx> CompilerContext {
         self.else_if_branches.alloc_slice_copy(else_if_branches)
     }
 
-    pub(crate) fn alloc_slice_of_param<'a>(
-        &'ctx self,
-        params: &'a [Param],
-    ) -> &'ctx [Param] {
+    pub(crate) fn alloc_slice_of_param<'a>(&'ctx self, params: &'a [Param]) -> &'ctx [Param] {
         self.params.alloc_slice_copy(params)
     }
+
+    pub(crate) fn alloc_slice_of_span<'a>(&'ctx self, spans: &'a [Span]) -> &'ctx [Span] {
+        self.spans.alloc_slice_copy(spans)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct Location {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
 }
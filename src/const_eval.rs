@@ -0,0 +1,644 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{
+    AssignExpr, BinaryExpr, BinaryOp, CompoundExpr, Const, Decl, Expr, ExprKind, FnCallExpr,
+    ForExpr, ForIteration, IfExpr, IntType, NodeId, Program, RangeKind, Type, UnaryExpr, UnaryOp,
+};
+use crate::compiler_context::CompilerContext;
+use crate::diagnostic::Diagnostic;
+use crate::interner::Symbol;
+use crate::labels::LoopTargets;
+use crate::resolver::{Definition, NameResolution};
+use crate::scanner::Span;
+use crate::typeck::{binary_op_symbol, Ty, TypeTable};
+
+/// Why an expression has no value at compile time.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum EvalError {
+    /// The value depends on something that is only known at run time, such as
+    /// a parameter or a global, at this span.
+    NotConstant(Span),
+    /// The evaluation took too many steps or nested calls, such as in a loop
+    /// that never ends.
+    LimitReached,
+    /// The evaluation hit an error that is already reported, such as an
+    /// overflow.
+    Reported,
+}
+
+/// What cuts the evaluation of an expression short.
+enum Interrupt {
+    Break { loop_id: NodeId, value: i128 },
+    Continue { loop_id: NodeId },
+    Return(i128),
+    Error(EvalError),
+}
+
+impl From<EvalError> for Interrupt {
+    fn from(error: EvalError) -> Interrupt {
+        Interrupt::Error(error)
+    }
+}
+
+/// Evaluates expressions at compile time, by running them over the tree.
+/// Values are the integers they stand for, with `bool`s as `0` or `1` and
+/// `()` as `0`.
+///
+/// - Arithmetic that overflows its type is an error, as is dividing by zero or
+///   shifting by the type's width or more. So is a range whose step isn't
+///   positive, as its loop would never end.
+/// - Functions can be called as long as they only touch their own parameters
+///   and bindings, and constants.
+/// - The evaluation gives up after a number of steps or nested calls, so that
+///   loops that never end don't hang the compiler.
+pub(crate) struct ConstEvaluator<'ctx> {
+    ctx: &'ctx CompilerContext,
+    name_resolution: &'ctx NameResolution,
+    loop_targets: &'ctx LoopTargets,
+    types: &'ctx TypeTable,
+    /// The first declaration of each top-level name.
+    decl_by_symbol: HashMap<Symbol, &'ctx Decl<'ctx>>,
+    value_by_constant: HashMap<Symbol, Result<i128, EvalError>>,
+    /// Names of the constants whose values are being evaluated, the innermost
+    /// last, to find those that depend on themselves through a function, and
+    /// to tell which one an error deep in a function is found for.
+    constants_being_evaluated: Vec<Symbol>,
+    /// Values of the parameters and bindings of each function call being
+    /// evaluated, the innermost last.
+    frames: Vec<HashMap<Definition, i128>>,
+    steps_left: u32,
+    /// Steps of ranges that are reported not to be positive, by their id, as
+    /// the same loop may be evaluated many times, and generated as well.
+    non_positive_steps: HashSet<NodeId>,
+}
+
+impl<'ctx> ConstEvaluator<'ctx> {
+    pub(crate) const STEP_LIMIT: u32 = 100_000;
+    pub(crate) const CALL_DEPTH_LIMIT: usize = 256;
+
+    pub(crate) fn new(
+        ctx: &'ctx CompilerContext,
+        name_resolution: &'ctx NameResolution,
+        loop_targets: &'ctx LoopTargets,
+        types: &'ctx TypeTable,
+        program: Program<'ctx>,
+    ) -> ConstEvaluator<'ctx> {
+        let mut decl_by_symbol = HashMap::new();
+
+        for decl in program.decls {
+            decl_by_symbol.entry(decl.identifier).or_insert(decl);
+        }
+
+        ConstEvaluator {
+            ctx,
+            name_resolution,
+            loop_targets,
+            types,
+            decl_by_symbol,
+            value_by_constant: HashMap::new(),
+            constants_being_evaluated: vec![],
+            frames: vec![],
+            steps_left: 0,
+            non_positive_steps: HashSet::new(),
+        }
+    }
+
+    /// Finds the value of the top-level constant with this name, which is
+    /// evaluated only once.
+    pub(crate) fn value_of_constant(&mut self, identifier: Symbol) -> Result<i128, EvalError> {
+        if let Some(&result) = self.value_by_constant.get(&identifier) {
+            return result;
+        }
+
+        self.constants_being_evaluated.push(identifier);
+        let result = self.eval(self.decl_by_symbol[&identifier].value);
+        self.constants_being_evaluated.pop();
+
+        self.value_by_constant.insert(identifier, result);
+
+        result
+    }
+
+    /// Evaluates an expression on its own, with a step budget of its own.
+    /// Errors that make the program wrong, such as overflows, are reported
+    /// along the way, while the caller decides what to do about values that
+    /// aren't constant.
+    pub(crate) fn eval(&mut self, expr: &Expr) -> Result<i128, EvalError> {
+        let outer_frames = std::mem::replace(&mut self.frames, vec![HashMap::new()]);
+        let outer_steps_left = std::mem::replace(&mut self.steps_left, Self::STEP_LIMIT);
+
+        let result = match self.eval_expr(expr) {
+            Ok(value) => Ok(value),
+            Err(Interrupt::Error(error)) => Err(error),
+            // Jumps out of the expression, such as a `return` in the end of a
+            // range, only happen at run time.
+            Err(Interrupt::Break { .. } | Interrupt::Continue { .. } | Interrupt::Return(_)) => {
+                Err(EvalError::NotConstant(expr.span))
+            }
+        };
+
+        self.frames = outer_frames;
+        self.steps_left = outer_steps_left;
+
+        result
+    }
+
+    /// Evaluates the step of a range on its own, like `eval`, reporting it if
+    /// it isn't positive.
+    pub(crate) fn eval_step(&mut self, step_expr: &Expr) -> Result<i128, EvalError> {
+        match self.eval(step_expr) {
+            Ok(step) if step <= 0 => {
+                self.report_non_positive_step(step_expr, step);
+
+                Err(EvalError::Reported)
+            }
+            result => result,
+        }
+    }
+
+    fn eval_expr(&mut self, expr: &Expr) -> Result<i128, Interrupt> {
+        self.take_step()?;
+
+        match expr.kind {
+            ExprKind::Const(Const::Unit) => Ok(0),
+            ExprKind::Const(Const::IntegerConstant { value, .. }) => Ok(i128::from(value)),
+            ExprKind::Const(Const::BoolConstant { value }) => Ok(i128::from(value)),
+            ExprKind::BindRef(_) => self.eval_bind_ref_expr(expr),
+            ExprKind::BindDef(bind_def) => {
+                let value = self.eval_expr(bind_def.value)?;
+                self.this_frame().insert(Definition::Local(expr.id), value);
+
+                Ok(0)
+            }
+            ExprKind::Assign(assign_expr) => self.eval_assign_expr(expr, assign_expr),
+            ExprKind::If(if_expr) => self.eval_if_expr(if_expr),
+            ExprKind::For(for_expr) => self.eval_for_expr(expr.id, for_expr),
+            ExprKind::Break(break_expr) => {
+                let value = match break_expr.value {
+                    Some(value) => self.eval_expr(value)?,
+                    None => 0,
+                };
+
+                Err(Interrupt::Break {
+                    loop_id: self.target_loop_of(expr)?,
+                    value,
+                })
+            }
+            ExprKind::Continue(_) => Err(Interrupt::Continue {
+                loop_id: self.target_loop_of(expr)?,
+            }),
+            ExprKind::Return(value) => {
+                let value = match value {
+                    Some(value) => self.eval_expr(value)?,
+                    None => 0,
+                };
+
+                Err(Interrupt::Return(value))
+            }
+            ExprKind::Compound(compound_expr) => self.eval_compound_expr(compound_expr),
+            ExprKind::Semi(expr) => {
+                self.eval_expr(expr)?;
+
+                Ok(0)
+            }
+            ExprKind::FnCall(fn_call_expr) => self.eval_fn_call_expr(expr, fn_call_expr),
+            ExprKind::Binary(binary_expr) => self.eval_binary_expr(expr, binary_expr),
+            ExprKind::Unary(unary_expr) => self.eval_unary_expr(expr, unary_expr),
+            ExprKind::Cast(cast_expr) => {
+                let value = self.eval_expr(cast_expr.operand)?;
+
+                match cast_expr.ty {
+                    Type::Int(int_type) => Ok(wrap_to(value, int_type)),
+                    Type::Unit | Type::Bool => Ok(value),
+                }
+            }
+            ExprKind::Function(_) => Err(EvalError::NotConstant(expr.span).into()),
+            ExprKind::Error => Err(EvalError::Reported.into()),
+        }
+    }
+
+    fn eval_bind_ref_expr(&mut self, expr: &Expr) -> Result<i128, Interrupt> {
+        match self.name_resolution.get(expr.id) {
+            Some(Definition::TopLevel(identifier)) => {
+                let decl = self.decl_by_symbol[&identifier];
+
+                if decl.is_mutable || matches!(decl.value.kind, ExprKind::Function(_)) {
+                    return Err(EvalError::NotConstant(expr.span).into());
+                }
+
+                if self.constants_being_evaluated.contains(&identifier) {
+                    self.report(Diagnostic::error(
+                        format!(
+                            "the value of `{}` depends on itself",
+                            self.ctx.resolve_symbol(identifier)
+                        ),
+                        expr.span,
+                    ));
+
+                    return Err(EvalError::Reported.into());
+                }
+
+                // Whatever is wrong with the constant's value is reported along
+                // with the constant itself.
+                self.value_of_constant(identifier)
+                    .map_err(|_| EvalError::Reported.into())
+            }
+            Some(definition) => self.value_of_local(definition, expr.span),
+            None => Err(EvalError::Reported.into()),
+        }
+    }
+
+    /// Finds the value of a parameter or binding of the function call being
+    /// evaluated. Others, such as those of the function being generated,
+    /// aren't known.
+    fn value_of_local(&mut self, definition: Definition, span: Span) -> Result<i128, Interrupt> {
+        self.this_frame()
+            .get(&definition)
+            .copied()
+            .ok_or(EvalError::NotConstant(span).into())
+    }
+
+    fn eval_assign_expr(
+        &mut self,
+        assign_expr_node: &Expr,
+        assign_expr: AssignExpr,
+    ) -> Result<i128, Interrupt> {
+        let definition = match self.name_resolution.get(assign_expr_node.id) {
+            Some(Definition::TopLevel(_)) => {
+                return Err(EvalError::NotConstant(assign_expr_node.span).into())
+            }
+            Some(definition) => definition,
+            None => return Err(EvalError::Reported.into()),
+        };
+
+        let mut value = self.eval_expr(assign_expr.value)?;
+
+        if let Some(op) = assign_expr.op {
+            let current_value = self.value_of_local(definition, assign_expr_node.span)?;
+            let int_type = self.int_type_of(assign_expr.value)?;
+
+            value =
+                self.eval_arithmetic(op, current_value, value, int_type, assign_expr_node.span)?;
+        }
+
+        self.this_frame().insert(definition, value);
+
+        Ok(0)
+    }
+
+    fn eval_if_expr(&mut self, if_expr: IfExpr) -> Result<i128, Interrupt> {
+        let branches = std::iter::once((if_expr.cond_expr, if_expr.true_branch)).chain(
+            if_expr
+                .else_if_branches
+                .iter()
+                .map(|branch| (branch.cond_expr, branch.true_branch)),
+        );
+
+        for (cond_expr, true_branch) in branches {
+            if self.eval_expr(cond_expr)? != 0 {
+                return self.eval_compound_expr(true_branch);
+            }
+        }
+
+        match if_expr.final_branch {
+            Some(final_branch) => self.eval_compound_expr(final_branch),
+            None => Ok(0),
+        }
+    }
+
+    /// Runs a loop the way its generated code does, including ending an
+    /// iterative loop once its variable can't step any further.
+    fn eval_for_expr(&mut self, for_id: NodeId, for_expr: ForExpr) -> Result<i128, Interrupt> {
+        match for_expr.iteration {
+            None => loop {
+                if let Some(value) = self.eval_loop_body(for_id, for_expr.body)? {
+                    return Ok(value);
+                }
+            },
+            Some(ForIteration::Conditional { cond_expr }) => loop {
+                if self.eval_expr(cond_expr)? == 0 {
+                    return Ok(0);
+                }
+
+                if let Some(value) = self.eval_loop_body(for_id, for_expr.body)? {
+                    return Ok(value);
+                }
+            },
+            Some(ForIteration::Iterative {
+                start_expr,
+                end_expr,
+                range_kind,
+                step_expr,
+                is_descending,
+                ..
+            }) => {
+                let int_type = self.int_type_of(start_expr)?;
+                let mut value = self.eval_expr(start_expr)?;
+                let end = self.eval_expr(end_expr)?;
+                let step = match step_expr {
+                    Some(step_expr) => self.eval_expr(step_expr)?,
+                    None => 1,
+                };
+
+                if let Some(step_expr) = step_expr.filter(|_| step <= 0) {
+                    self.report_non_positive_step(step_expr, step);
+
+                    return Err(EvalError::Reported.into());
+                }
+
+                loop {
+                    let is_done = match (range_kind, is_descending) {
+                        (RangeKind::Inclusive, false) => value > end,
+                        (RangeKind::Exclusive, false) => value >= end,
+                        (RangeKind::Inclusive, true) => value < end,
+                        (RangeKind::Exclusive, true) => value <= end,
+                    };
+
+                    if is_done {
+                        return Ok(0);
+                    }
+
+                    self.this_frame().insert(Definition::Local(for_id), value);
+
+                    if self.eval_loop_body(for_id, for_expr.body)?.is_some() {
+                        return Ok(0);
+                    }
+
+                    value = if is_descending {
+                        value - step
+                    } else {
+                        value + step
+                    };
+
+                    if !fits(value, int_type) {
+                        return Ok(0);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Runs the body of a loop once, and returns the value given by a `break`
+    /// out of it, if any.
+    fn eval_loop_body(
+        &mut self,
+        for_id: NodeId,
+        body: CompoundExpr,
+    ) -> Result<Option<i128>, Interrupt> {
+        // Each iteration is a step of its own, so that even a loop with an empty
+        // body runs out of them.
+        self.take_step()?;
+
+        match self.eval_compound_expr(body) {
+            Ok(_) => Ok(None),
+            Err(Interrupt::Break { loop_id, value }) if loop_id == for_id => Ok(Some(value)),
+            Err(Interrupt::Continue { loop_id }) if loop_id == for_id => Ok(None),
+            Err(interrupt) => Err(interrupt),
+        }
+    }
+
+    fn eval_compound_expr(&mut self, compound_expr: CompoundExpr) -> Result<i128, Interrupt> {
+        let mut value = 0;
+
+        for expr in compound_expr.exprs {
+            value = self.eval_expr(expr)?;
+        }
+
+        Ok(value)
+    }
+
+    fn eval_fn_call_expr(
+        &mut self,
+        call_expr: &Expr,
+        fn_call_expr: FnCallExpr,
+    ) -> Result<i128, Interrupt> {
+        let Some(Definition::TopLevel(identifier)) = self.name_resolution.get(call_expr.id) else {
+            return Err(EvalError::Reported.into());
+        };
+
+        let function_expr = self.decl_by_symbol[&identifier].value;
+
+        let ExprKind::Function(function) = function_expr.kind else {
+            return Err(EvalError::Reported.into());
+        };
+
+        let mut frame = HashMap::new();
+
+        for (index, argument) in fn_call_expr.arguments.iter().enumerate() {
+            let param = Definition::Param {
+                function: function_expr.id,
+                index,
+            };
+
+            frame.insert(param, self.eval_expr(argument)?);
+        }
+
+        if self.frames.len() >= Self::CALL_DEPTH_LIMIT {
+            return Err(EvalError::LimitReached.into());
+        }
+
+        self.frames.push(frame);
+        let result = self.eval_compound_expr(function.body);
+        self.frames.pop();
+
+        match result {
+            Ok(value) | Err(Interrupt::Return(value)) => Ok(value),
+            Err(interrupt) => Err(interrupt),
+        }
+    }
+
+    fn eval_binary_expr(
+        &mut self,
+        binary_expr_node: &Expr,
+        binary_expr: BinaryExpr,
+    ) -> Result<i128, Interrupt> {
+        let lhs = self.eval_expr(binary_expr.lhs)?;
+
+        // The right-hand side of `&&` and `||` is only evaluated if needed.
+        match (binary_expr.op, lhs) {
+            (BinaryOp::LogicalAnd, 0) => return Ok(0),
+            (BinaryOp::LogicalOr, 1) => return Ok(1),
+            _ => {}
+        }
+
+        let rhs = self.eval_expr(binary_expr.rhs)?;
+
+        let value = match binary_expr.op {
+            BinaryOp::Less => lhs < rhs,
+            BinaryOp::Greater => lhs > rhs,
+            BinaryOp::LessEqual => lhs <= rhs,
+            BinaryOp::GreaterEqual => lhs >= rhs,
+            BinaryOp::Equal => lhs == rhs,
+            BinaryOp::NotEqual => lhs != rhs,
+            BinaryOp::LogicalAnd | BinaryOp::LogicalOr => rhs != 0,
+            op => {
+                let int_type = self.int_type_of(binary_expr_node)?;
+
+                return self.eval_arithmetic(op, lhs, rhs, int_type, binary_expr_node.span);
+            }
+        };
+
+        Ok(i128::from(value))
+    }
+
+    /// Applies an arithmetic operator to a pair of values of `int_type`, and
+    /// reports the error at `span` if the result doesn't fit in it.
+    fn eval_arithmetic(
+        &self,
+        op: BinaryOp,
+        lhs: i128,
+        rhs: i128,
+        int_type: IntType,
+        span: Span,
+    ) -> Result<i128, Interrupt> {
+        let bits = 8 * int_type.size_in_bytes() as i128;
+
+        let value = match op {
+            BinaryOp::Add => Some(lhs + rhs),
+            BinaryOp::Sub => Some(lhs - rhs),
+            BinaryOp::Mul => lhs.checked_mul(rhs),
+            BinaryOp::Div if rhs == 0 => {
+                self.report(Diagnostic::error(
+                    format!("attempt to divide `{}` by zero", lhs),
+                    span,
+                ));
+
+                return Err(EvalError::Reported.into());
+            }
+            BinaryOp::Div => Some(lhs / rhs),
+            BinaryOp::Shl | BinaryOp::Shr if !(0..bits).contains(&rhs) => None,
+            // Bits shifted out to the left are just dropped.
+            BinaryOp::Shl => Some(wrap_to(lhs << rhs, int_type)),
+            BinaryOp::Shr => Some(lhs >> rhs),
+            _ => unreachable!("not an arithmetic operator"),
+        };
+
+        match value {
+            Some(value) if fits(value, int_type) => Ok(value),
+            _ => {
+                self.report(Diagnostic::error(
+                    format!(
+                        "attempt to compute `{} {} {}`, which overflows `{}`",
+                        lhs,
+                        binary_op_symbol(op),
+                        rhs,
+                        int_type.name()
+                    ),
+                    span,
+                ));
+
+                Err(EvalError::Reported.into())
+            }
+        }
+    }
+
+    fn eval_unary_expr(
+        &mut self,
+        unary_expr_node: &Expr,
+        unary_expr: UnaryExpr,
+    ) -> Result<i128, Interrupt> {
+        let operand = self.eval_expr(unary_expr.operand)?;
+
+        match unary_expr.op {
+            UnaryOp::Neg => {
+                let int_type = self.int_type_of(unary_expr_node)?;
+
+                if !fits(-operand, int_type) {
+                    self.report(Diagnostic::error(
+                        format!(
+                            "attempt to negate `{}`, which overflows `{}`",
+                            operand,
+                            int_type.name()
+                        ),
+                        unary_expr_node.span,
+                    ));
+
+                    return Err(EvalError::Reported.into());
+                }
+
+                Ok(-operand)
+            }
+            UnaryOp::Not => Ok(i128::from(operand == 0)),
+        }
+    }
+
+    fn report_non_positive_step(&mut self, step_expr: &Expr, step: i128) {
+        if self.non_positive_steps.insert(step_expr.id) {
+            self.report(
+                Diagnostic::error(
+                    format!("the step of a range must be positive, but it's `{}`", step),
+                    step_expr.span,
+                )
+                .with_help("to count down, write the step with a leading `-`, as in `by -2`"),
+            );
+        }
+    }
+
+    /// Reports an error found along the evaluation. If it's outside the value
+    /// of the constant being evaluated, such as deep in a function that the
+    /// value calls, the value is pointed at as well.
+    fn report(&self, mut diagnostic: Diagnostic) {
+        if let Some(identifier) = self.constants_being_evaluated.last() {
+            let value_span = self.decl_by_symbol[identifier].value.span;
+            let is_in_value = value_span.start <= diagnostic.primary_span.start
+                && diagnostic.primary_span.end <= value_span.end;
+
+            if !is_in_value {
+                diagnostic = diagnostic.with_label(
+                    value_span,
+                    format!(
+                        "while evaluating `{}` here",
+                        self.ctx.resolve_symbol(*identifier)
+                    ),
+                );
+            }
+        }
+
+        self.ctx.report(diagnostic);
+    }
+
+    fn take_step(&mut self) -> Result<(), Interrupt> {
+        if self.steps_left == 0 {
+            return Err(EvalError::LimitReached.into());
+        }
+
+        self.steps_left -= 1;
+
+        Ok(())
+    }
+
+    fn target_loop_of(&self, expr: &Expr) -> Result<NodeId, Interrupt> {
+        self.loop_targets
+            .get(expr.id)
+            .ok_or(EvalError::Reported.into())
+    }
+
+    fn int_type_of(&self, expr: &Expr) -> Result<IntType, Interrupt> {
+        match self.types.get(expr.id) {
+            Ty::Value(Type::Int(int_type)) => Ok(int_type),
+            _ => Err(EvalError::Reported.into()),
+        }
+    }
+
+    fn this_frame(&mut self) -> &mut HashMap<Definition, i128> {
+        self.frames
+            .last_mut()
+            .expect("evaluation always starts with a frame")
+    }
+}
+
+fn fits(value: i128, int_type: IntType) -> bool {
+    (int_type.min_value()..=int_type.max_value()).contains(&value)
+}
+
+/// Keeps the bits of a value that fit in `int_type`, as a cast does.
+fn wrap_to(value: i128, int_type: IntType) -> i128 {
+    let modulus = 1i128 << (8 * int_type.size_in_bytes());
+    let value = value.rem_euclid(modulus);
+
+    if value > int_type.max_value() {
+        value - modulus
+    } else {
+        value
+    }
+}
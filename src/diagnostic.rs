@@ -0,0 +1,72 @@
+use crate::scanner::Span;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found in the source code, pointing at where it happened.
+#[derive(Clone, Debug)]
+pub(crate) struct Diagnostic {
+    pub(crate) severity: Severity,
+    pub(crate) message: String,
+    pub(crate) primary_span: Span,
+    /// Other places in the source code that help explain the problem.
+    pub(crate) labels: Vec<Label>,
+    pub(crate) notes: Vec<String>,
+    /// Suggestions of how to fix the problem, shown after the notes.
+    pub(crate) helps: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Label {
+    pub(crate) span: Span,
+    pub(crate) message: String,
+}
+
+impl Diagnostic {
+    pub(crate) fn error(message: impl Into<String>, primary_span: Span) -> Diagnostic {
+        Diagnostic::new(Severity::Error, message.into(), primary_span)
+    }
+
+    pub(crate) fn warning(message: impl Into<String>, primary_span: Span) -> Diagnostic {
+        Diagnostic::new(Severity::Warning, message.into(), primary_span)
+    }
+
+    fn new(severity: Severity, message: String, primary_span: Span) -> Diagnostic {
+        Diagnostic {
+            severity,
+            message,
+            primary_span,
+            labels: vec![],
+            notes: vec![],
+            helps: vec![],
+        }
+    }
+
+    pub(crate) fn with_label(mut self, span: Span, message: impl Into<String>) -> Diagnostic {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+
+        self
+    }
+
+    pub(crate) fn with_note(mut self, note: impl Into<String>) -> Diagnostic {
+        self.notes.push(note.into());
+
+        self
+    }
+
+    pub(crate) fn with_help(mut self, help: impl Into<String>) -> Diagnostic {
+        self.helps.push(help.into());
+
+        self
+    }
+
+    pub(crate) fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
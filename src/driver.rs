@@ -1,22 +1,47 @@
-use crate::codegen::CodeGen;
+use crate::codegen::{CodeGen, OutputKind};
 use crate::compiler_context::CompilerContext;
+use crate::const_eval::ConstEvaluator;
+use crate::diagnostic::Diagnostic;
+use crate::labels::LabelResolver;
 use crate::parser::Parser;
+use crate::resolver::Resolver;
 use crate::scanner::Scanner;
+use crate::typeck::TypeChecker;
 
-pub(crate) fn compile(source_code: &str) -> String {
-    // FIXME: don't copy source code, move it.
-    let context = CompilerContext::new(source_code.into());
-
+/// Compiles the source code of the context to x86 assembly of the given kind,
+/// or returns every diagnostic reported along the way if any of them is an
+/// error. The caller keeps the context around, such as to render the
+/// diagnostics against its source code afterwards. Warnings of a successful
+/// compilation are left in the context.
+pub(crate) fn compile(
+    context: &CompilerContext,
+    output_kind: OutputKind,
+) -> Result<String, Vec<Diagnostic>> {
     let tokens = {
-        let mut scanner = Scanner::new(&context);
+        let mut scanner = Scanner::new(context);
         scanner.scan_all_tokens()
     };
 
-    let mut parser = Parser::new(tokens, &context);
-    let program = parser.parse_program().unwrap();
+    // Names are still resolved and types checked after syntax errors, as those
+    // passes report errors of their own that are worth seeing in the same run.
+    let mut parser = Parser::new(tokens, context);
+    let program = parser.parse_program();
+
+    let name_resolution = Resolver::new(context).resolve_program(program);
+    let loop_targets = LabelResolver::new(context, &name_resolution).resolve_program(program);
+    let types = TypeChecker::new(context, &name_resolution, &loop_targets).check_program(program);
 
-    let mut codegen = CodeGen::new(&context);
+    if context.has_errors() {
+        return Err(context.take_diagnostics());
+    }
+
+    let const_eval = ConstEvaluator::new(context, &name_resolution, &loop_targets, &types, program);
+    let mut codegen = CodeGen::new(context, &types, &loop_targets, const_eval, output_kind);
     let x86_program = codegen.gen_program(program);
 
-    format!("{}", x86_program)
+    if context.has_errors() {
+        return Err(context.take_diagnostics());
+    }
+
+    Ok(format!("{}", x86_program))
 }
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use crate::ast::{CompoundExpr, Expr, ExprKind, ForIteration, Label, NodeId, Program};
+use crate::compiler_context::CompilerContext;
+use crate::diagnostic::Diagnostic;
+use crate::resolver::NameResolution;
+
+/// The loop that each `break` and `continue` leaves or restarts, as the id of
+/// its `for` expression, keyed by the id of the `break` or `continue`.
+#[derive(Default)]
+pub(crate) struct LoopTargets {
+    target_by_node: HashMap<NodeId, NodeId>,
+}
+
+impl LoopTargets {
+    pub(crate) fn get(&self, node_id: NodeId) -> Option<NodeId> {
+        self.target_by_node.get(&node_id).copied()
+    }
+}
+
+/// Finds the loop that each `break` and `continue` refers to:
+///
+/// - Without a label, it's the innermost loop around it.
+/// - With a label, it's the innermost loop around it that has that label.
+/// - Loops don't reach into the function literals inside of them.
+pub(crate) struct LabelResolver<'ctx> {
+    ctx: &'ctx CompilerContext,
+    name_resolution: &'ctx NameResolution,
+    /// Loops around the expression being resolved, the innermost last.
+    loop_stack: Vec<(NodeId, Option<Label>)>,
+    targets: LoopTargets,
+}
+
+impl<'ctx> LabelResolver<'ctx> {
+    pub(crate) fn new(
+        ctx: &'ctx CompilerContext,
+        name_resolution: &'ctx NameResolution,
+    ) -> LabelResolver<'ctx> {
+        LabelResolver {
+            ctx,
+            name_resolution,
+            loop_stack: vec![],
+            targets: LoopTargets::default(),
+        }
+    }
+
+    pub(crate) fn resolve_program(&mut self, program: Program) -> LoopTargets {
+        for decl in program.decls {
+            self.resolve_expr(decl.value);
+        }
+
+        std::mem::take(&mut self.targets)
+    }
+
+    fn resolve_expr(&mut self, expr: &Expr) {
+        match expr.kind {
+            ExprKind::Const(_) | ExprKind::BindRef(_) | ExprKind::Error => {}
+            ExprKind::BindDef(bind_def) => self.resolve_expr(bind_def.value),
+            ExprKind::Assign(assign_expr) => self.resolve_expr(assign_expr.value),
+            ExprKind::Function(function) => {
+                let outer_loop_stack = std::mem::take(&mut self.loop_stack);
+
+                self.resolve_compound_expr(function.body);
+
+                self.loop_stack = outer_loop_stack;
+            }
+            ExprKind::If(if_expr) => {
+                self.resolve_expr(if_expr.cond_expr);
+                self.resolve_compound_expr(if_expr.true_branch);
+
+                for branch in if_expr.else_if_branches {
+                    self.resolve_expr(branch.cond_expr);
+                    self.resolve_compound_expr(branch.true_branch);
+                }
+
+                if let Some(final_branch) = if_expr.final_branch {
+                    self.resolve_compound_expr(final_branch);
+                }
+            }
+            ExprKind::For(for_expr) => {
+                match for_expr.iteration {
+                    Some(ForIteration::Conditional { cond_expr }) => self.resolve_expr(cond_expr),
+                    Some(ForIteration::Iterative {
+                        start_expr,
+                        end_expr,
+                        step_expr,
+                        ..
+                    }) => {
+                        self.resolve_expr(start_expr);
+                        self.resolve_expr(end_expr);
+
+                        if let Some(step_expr) = step_expr {
+                            self.resolve_expr(step_expr);
+                        }
+                    }
+                    None => {}
+                }
+
+                if let Some(label) = for_expr.label {
+                    self.check_label_shadowing(label);
+                }
+
+                self.loop_stack.push((expr.id, for_expr.label));
+                self.resolve_compound_expr(for_expr.body);
+                self.loop_stack.pop();
+            }
+            ExprKind::Break(break_expr) => {
+                if let Some(value) = break_expr.value {
+                    self.resolve_expr(value);
+                }
+
+                // A name that `break` leaves with is a label if it's not a binding.
+                let label = match (break_expr.value_as_label(), break_expr.value) {
+                    (Some(label), Some(value)) if self.name_resolution.get(value.id).is_none() => {
+                        Some(label)
+                    }
+                    _ => break_expr.label,
+                };
+
+                self.resolve_target(expr, "break", label);
+            }
+            ExprKind::Continue(label) => self.resolve_target(expr, "continue", label),
+            ExprKind::Return(value) => {
+                if let Some(value) = value {
+                    self.resolve_expr(value);
+                }
+            }
+            ExprKind::Compound(compound_expr) => self.resolve_compound_expr(compound_expr),
+            ExprKind::Semi(expr) => self.resolve_expr(expr),
+            ExprKind::FnCall(fn_call_expr) => {
+                for argument in fn_call_expr.arguments {
+                    self.resolve_expr(argument);
+                }
+            }
+            ExprKind::Binary(binary_expr) => {
+                self.resolve_expr(binary_expr.lhs);
+                self.resolve_expr(binary_expr.rhs);
+            }
+            ExprKind::Unary(unary_expr) => self.resolve_expr(unary_expr.operand),
+            ExprKind::Cast(cast_expr) => self.resolve_expr(cast_expr.operand),
+        }
+    }
+
+    fn resolve_compound_expr(&mut self, compound_expr: CompoundExpr) {
+        for expr in compound_expr.exprs {
+            self.resolve_expr(expr);
+        }
+    }
+
+    /// Records the loop that a `break` or `continue` refers to, where `keyword`
+    /// is the one it's written with.
+    fn resolve_target(&mut self, expr: &Expr, keyword: &str, label: Option<Label>) {
+        let target = match label {
+            Some(label) => self.find_labeled_loop(label).map(|(loop_id, _)| loop_id),
+            None => self.loop_stack.last().map(|&(loop_id, _)| loop_id),
+        };
+
+        match (target, label) {
+            (Some(loop_id), _) => {
+                self.targets.target_by_node.insert(expr.id, loop_id);
+            }
+            (None, Some(label)) => self.ctx.report(Diagnostic::error(
+                format!(
+                    "cannot find label `{}` in this scope",
+                    self.ctx.resolve_symbol(label.identifier)
+                ),
+                label.span,
+            )),
+            (None, None) => self.ctx.report(Diagnostic::error(
+                format!("`{}` outside of a loop", keyword),
+                expr.span,
+            )),
+        }
+    }
+
+    /// Finds the innermost loop around the expression being resolved that has
+    /// the same label, along with the label's span there.
+    fn find_labeled_loop(&self, label: Label) -> Option<(NodeId, Label)> {
+        self.loop_stack
+            .iter()
+            .rev()
+            .find_map(|&(loop_id, loop_label)| match loop_label {
+                Some(loop_label) if loop_label.identifier == label.identifier => {
+                    Some((loop_id, loop_label))
+                }
+                _ => None,
+            })
+    }
+
+    fn check_label_shadowing(&self, label: Label) {
+        if let Some((_, outer_label)) = self.find_labeled_loop(label) {
+            let name = self.ctx.resolve_symbol(label.identifier);
+
+            self.ctx.report(
+                Diagnostic::warning(
+                    format!("label `{}` shadows the label of an enclosing loop", name),
+                    label.span,
+                )
+                .with_label(
+                    outer_label.span,
+                    format!("`{}` is first declared here", name),
+                )
+                .with_note("`break` and `continue` will refer to the innermost loop"),
+            );
+        }
+    }
+}
@@ -1,18 +1,75 @@
 #![feature(hash_raw_entry, hasher_prefixfree_extras)]
 
+use std::io::IsTerminal;
+use std::process::ExitCode;
+use std::{env, fs, io};
+
+use crate::codegen::OutputKind;
+use crate::compiler_context::CompilerContext;
 use crate::driver::compile;
+use crate::renderer::Renderer;
 
 mod ast;
 mod codegen;
 mod compiler_context;
+mod const_eval;
+mod diagnostic;
 mod driver;
 mod interner;
+mod labels;
 mod parser;
+mod renderer;
+mod resolver;
 mod scanner;
+mod typeck;
 
 #[cfg(test)]
 mod tests;
 
-fn main() {
-    let _ = compile("main :: () {}");
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1).peekable();
+
+    // With `--freestanding`, the program gets an entry point of its own instead of
+    // libc's.
+    let output_kind = if args.next_if_eq("--freestanding").is_some() {
+        OutputKind::FreestandingFile
+    } else {
+        OutputKind::File
+    };
+
+    let Some(file_name) = args.next() else {
+        eprintln!("usage: sophia [--freestanding] <file>");
+        return ExitCode::FAILURE;
+    };
+
+    let source_code = match fs::read_to_string(&file_name) {
+        Ok(source_code) => source_code,
+        Err(error) => {
+            eprintln!("error: couldn't read `{}`: {}", file_name, error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let context = CompilerContext::new(source_code);
+
+    let result = compile(&context, output_kind);
+
+    let diagnostics = match &result {
+        Ok(_) => context.take_diagnostics(),
+        Err(diagnostics) => diagnostics.clone(),
+    };
+
+    let renderer = Renderer::new(&context, &file_name, io::stderr().is_terminal());
+
+    for diagnostic in &diagnostics {
+        eprintln!("{}", renderer.render(diagnostic));
+    }
+
+    match result {
+        Ok(assembly) => {
+            print!("{}", assembly);
+            ExitCode::SUCCESS
+        }
+        Err(_) => ExitCode::FAILURE,
+    }
 }
@@ -1,132 +1,583 @@
 use crate::ast::*;
 use crate::compiler_context::CompilerContext;
-use crate::scanner::{Delim, Keyword, Token, TokenKind};
+use crate::diagnostic::Diagnostic;
+use crate::interner::Symbol;
+use crate::scanner::{BytePos, Delim, Keyword, Span, Token, TokenKind};
 
 pub(crate) struct Parser<'ctx> {
     ctx: &'ctx CompilerContext,
     tokens: Vec<Token>,
     current_token_idx: usize,
+    /// Doc comments are kept apart from the other tokens, and taken in order
+    /// as the declarations they document are parsed.
+    doc_comments: Vec<Token>,
+    next_doc_comment_idx: usize,
+    /// Labels of the loops around the code being parsed, the innermost last.
+    enclosing_labels: Vec<Symbol>,
+    next_node_id: u32,
 }
 
 impl<'ctx> Parser<'ctx> {
-    pub(crate) fn new(tokens: Vec<Token>, ctx: &'ctx CompilerContext) -> Parser {
+    const LOGICAL_OR_PRECEDENCE: u8 = 1;
+    const LOGICAL_AND_PRECEDENCE: u8 = 2;
+    const EQUALITY_PRECEDENCE: u8 = 3;
+    const RELATIONAL_PRECEDENCE: u8 = 4;
+    const SHIFT_PRECEDENCE: u8 = 5;
+    const FACTOR_PRECEDENCE: u8 = 6;
+    const PRODUCT_PRECEDENCE: u8 = 7;
+
+    pub(crate) fn new(tokens: Vec<Token>, ctx: &'ctx CompilerContext) -> Parser<'ctx> {
+        let (doc_comments, tokens) = tokens
+            .into_iter()
+            .partition(|tok| tok.kind == TokenKind::DocComment);
+
         Parser {
             ctx,
             tokens,
             current_token_idx: 0,
+            doc_comments,
+            next_doc_comment_idx: 0,
+            enclosing_labels: vec![],
+            next_node_id: 0,
         }
     }
 
-    pub(crate) fn parse_program(&mut self) -> Option<Program> {
+    /// Parses the whole program. Syntax errors are reported to the compiler
+    /// context and recovered from, so the program may be partial, with
+    /// `ExprKind::Error` nodes in place of the code that failed to parse.
+    pub(crate) fn parse_program(&mut self) -> Program<'ctx> {
         let mut decls = vec![];
 
-        while let Some(decl) = self.parse_decl() {
-            decls.push(decl);
+        while self.peek().is_some() {
+            match self.parse_decl() {
+                Some(decl) => decls.push(decl),
+                None => self.synchronize_to_decl(),
+            }
         }
 
-        Some(Program {
+        // Doc comments after the last declaration have nothing to document.
+        for doc_comment in &self.doc_comments[self.next_doc_comment_idx..] {
+            self.warn_unused_doc_comment(doc_comment.span);
+        }
+
+        Program {
             decls: self.ctx.alloc_slice_of_decl(&decls),
-        })
+        }
     }
 
     fn parse_decl(&mut self) -> Option<Decl<'ctx>> {
-        let ident_tok = self.consume()?;
-        debug_assert_eq!(ident_tok.kind, TokenKind::Identifier);
+        let doc_comments = match self.peek() {
+            Some(tok) => self.take_doc_comments_before(tok.span.start),
+            None => vec![],
+        };
 
-        let op = self.consume()?;
-        debug_assert_eq!(op.kind, TokenKind::ColonColon);
+        let is_mutable = self.is_at(TokenKind::Keyword(Keyword::Mut));
 
-        let expr = self.parse_statement_expr()?;
+        if is_mutable {
+            self.consume()?;
+        }
 
-        let identifier = self.ctx.get_or_intern_str(
-            &self.ctx.get_source_code()[ident_tok.span.start.0..ident_tok.span.end.0],
-        );
+        let ident_tok = self.expect(TokenKind::Identifier, "expected a declaration name")?;
+
+        if is_mutable {
+            self.expect(
+                TokenKind::ColonEqual,
+                "expected `:=` after the name of the global",
+            )?;
+        } else {
+            self.expect(
+                TokenKind::ColonColon,
+                "expected `::` after the declaration name",
+            )?;
+        }
+
+        let value_start = self.peek().map_or(ident_tok.span.end, |tok| tok.span.start);
+
+        // The declaration is kept even if its value fails to parse, so that its uses
+        // aren't reported as undefined.
+        let value = match self.parse_statement_expr() {
+            Some(value) => value,
+            None => {
+                self.synchronize_to_decl();
+                self.make_expr(ExprKind::Error, value_start)
+            }
+        };
 
         Some(Decl {
-            identifier,
-            value: self.ctx.alloc_expr(expr),
+            doc_comments: self.ctx.alloc_slice_of_span(&doc_comments),
+            identifier: self.intern_token(ident_tok),
+            identifier_span: ident_tok.span,
+            value: self.ctx.alloc_expr(value),
+            is_mutable,
         })
     }
 
     fn parse_statement_expr(&mut self) -> Option<Expr<'ctx>> {
-        let tok = self.consume()?;
+        match self.peek().map(|tok| tok.kind) {
+            Some(TokenKind::Keyword(Keyword::If)) => {
+                let if_tok = self.consume()?;
+                self.parse_if_expr(if_tok)
+            }
+            Some(TokenKind::Keyword(Keyword::For)) => {
+                let for_tok = self.consume()?;
+                self.parse_for_expr(None, for_tok)
+            }
+            Some(TokenKind::Identifier)
+                if self
+                    .look_ahead(1)
+                    .is_some_and(|tok| tok.kind == TokenKind::Colon)
+                    && self
+                        .look_ahead(2)
+                        .is_some_and(|tok| tok.kind == TokenKind::Keyword(Keyword::For)) =>
+            {
+                let label = self.parse_label()?;
+                self.consume()?;
+                let for_tok = self.consume()?;
+
+                self.parse_for_expr(Some(label), for_tok)
+            }
+            Some(TokenKind::UnitConstant | TokenKind::Open(Delim::Paren))
+                if self.is_at_function_literal() =>
+            {
+                let open_tok = self.consume()?;
+                self.parse_function(open_tok)
+            }
+            Some(TokenKind::Open(Delim::Curly)) => {
+                let open_curly_tok = self.consume()?;
+                let compound_expr = self.parse_compound_expr(open_curly_tok)?;
+
+                Some(self.make_expr(ExprKind::Compound(compound_expr), open_curly_tok.span.start))
+            }
+            Some(TokenKind::Keyword(Keyword::Mut)) => self.parse_bind_def_expr(),
+            Some(TokenKind::Identifier)
+                if self
+                    .look_ahead(1)
+                    .is_some_and(|tok| tok.kind == TokenKind::ColonEqual) =>
+            {
+                self.parse_bind_def_expr()
+            }
+            Some(TokenKind::Identifier)
+                if self
+                    .look_ahead(1)
+                    .is_some_and(|tok| Parser::assignment_op_of(tok.kind).is_some()) =>
+            {
+                self.parse_assign_expr()
+            }
+            _ => self.parse_logical_or_expr(),
+        }
+    }
+
+    fn parse_bind_def_expr(&mut self) -> Option<Expr<'ctx>> {
+        let mut_tok = if self.is_at(TokenKind::Keyword(Keyword::Mut)) {
+            self.consume()
+        } else {
+            None
+        };
+
+        let ident_tok = self.expect(TokenKind::Identifier, "expected a binding name")?;
+
+        self.expect(
+            TokenKind::ColonEqual,
+            "expected `:=` after the binding name",
+        )?;
+
+        let value = self.parse_statement_expr()?;
+
+        Some(self.make_expr(
+            ExprKind::BindDef(BindDef {
+                identifier: self.intern_token(ident_tok),
+                identifier_span: ident_tok.span,
+                value: self.ctx.alloc_expr(value),
+                is_mutable: mut_tok.is_some(),
+            }),
+            mut_tok.unwrap_or(ident_tok).span.start,
+        ))
+    }
+
+    fn parse_assign_expr(&mut self) -> Option<Expr<'ctx>> {
+        let ident_tok = self.expect(TokenKind::Identifier, "expected a binding name")?;
+
+        let op_tok = self.consume()?;
+        let op = Parser::assignment_op_of(op_tok.kind)
+            .expect("an assignment operator follows the binding name");
+
+        let value = self.parse_statement_expr()?;
+
+        Some(self.make_expr(
+            ExprKind::Assign(AssignExpr {
+                identifier: self.intern_token(ident_tok),
+                identifier_span: ident_tok.span,
+                op,
+                value: self.ctx.alloc_expr(value),
+            }),
+            ident_tok.span.start,
+        ))
+    }
+
+    fn parse_logical_or_expr(&mut self) -> Option<Expr<'ctx>> {
+        self.parse_binary_expr(Parser::LOGICAL_OR_PRECEDENCE)
+    }
+
+    /// Parses a chain of left-associative binary operators whose precedence is
+    /// at least `min_precedence`, following the operator levels laid out in
+    /// `grammar.ebnf`.
+    fn parse_binary_expr(&mut self, min_precedence: u8) -> Option<Expr<'ctx>> {
+        let mut lhs = self.parse_cast_expr()?;
+
+        while let Some(op) = self.peek().and_then(|tok| Parser::binary_op_of(tok.kind)) {
+            let precedence = Parser::precedence_of(op);
+
+            if precedence < min_precedence {
+                break;
+            }
+
+            self.consume()?;
+
+            let rhs = self.parse_binary_expr(precedence + 1)?;
+
+            lhs = self.make_expr(
+                ExprKind::Binary(BinaryExpr {
+                    op,
+                    lhs: self.ctx.alloc_expr(lhs),
+                    rhs: self.ctx.alloc_expr(rhs),
+                }),
+                lhs.span.start,
+            );
+        }
+
+        Some(lhs)
+    }
+
+    /// Parses a prefix expression followed by any number of `as` casts, which
+    /// bind tighter than binary operators but looser than prefix ones.
+    fn parse_cast_expr(&mut self) -> Option<Expr<'ctx>> {
+        let mut operand = self.parse_prefix_expr()?;
+
+        while self.is_at(TokenKind::Keyword(Keyword::As)) {
+            self.consume()?;
+
+            let ty = self.parse_type()?;
+
+            operand = self.make_expr(
+                ExprKind::Cast(CastExpr {
+                    operand: self.ctx.alloc_expr(operand),
+                    ty,
+                }),
+                operand.span.start,
+            );
+        }
+
+        Some(operand)
+    }
+
+    fn parse_prefix_expr(&mut self) -> Option<Expr<'ctx>> {
+        let op = match self.peek().map(|tok| tok.kind) {
+            Some(TokenKind::Dash) => UnaryOp::Neg,
+            Some(TokenKind::Excla) => UnaryOp::Not,
+            _ => return self.parse_postfix_expr(),
+        };
+
+        let op_tok = self.consume()?;
+
+        let operand = self.parse_prefix_expr()?;
+
+        Some(self.make_expr(
+            ExprKind::Unary(UnaryExpr {
+                op,
+                operand: self.ctx.alloc_expr(operand),
+            }),
+            op_tok.span.start,
+        ))
+    }
+
+    fn parse_postfix_expr(&mut self) -> Option<Expr<'ctx>> {
+        let Some(tok) = self.peek() else {
+            self.ctx
+                .report(self.unexpected_token_error("expected an expression"));
+
+            return None;
+        };
 
-        match tok.kind {
+        let kind = match tok.kind {
             TokenKind::IntegerConstant => {
-                let expr = Expr::Const(Const::IntegerConstant {
-                    value: self.ctx.get_source_code()[tok.span.start.0..tok.span.end.0]
-                        .parse::<i32>()
-                        .unwrap(),
-                });
+                self.consume()?;
+
+                // A malformed constant doesn't throw the parser off, so it just stands in as
+                // an error.
+                self.parse_integer_constant(tok)
+                    .map_or(ExprKind::Error, ExprKind::Const)
+            }
+            TokenKind::UnitConstant => {
+                self.consume()?;
+
+                ExprKind::Const(Const::Unit)
+            }
+            TokenKind::Keyword(keyword @ (Keyword::True | Keyword::False)) => {
+                self.consume()?;
 
-                Some(expr)
+                ExprKind::Const(Const::BoolConstant {
+                    value: keyword == Keyword::True,
+                })
+            }
+            TokenKind::Open(Delim::Paren) => {
+                let open_paren_tok = self.consume()?;
+
+                return self.parse_paren_expr(open_paren_tok);
+            }
+            TokenKind::Keyword(Keyword::Break) => {
+                let break_tok = self.consume()?;
+
+                return self.parse_break_expr(break_tok);
+            }
+            TokenKind::Keyword(Keyword::Continue) => {
+                let continue_tok = self.consume()?;
+
+                return self.parse_continue_expr(continue_tok);
+            }
+            TokenKind::Keyword(Keyword::Return) => {
+                let return_tok = self.consume()?;
+
+                return self.parse_return_expr(return_tok);
             }
-            TokenKind::Keyword(Keyword::If) => self.parse_if_expr(),
-            TokenKind::Keyword(Keyword::For) => self.parse_for_expr(),
-            TokenKind::Keyword(Keyword::Break) => self.parse_break_expr(),
-            TokenKind::Keyword(Keyword::Continue) => self.parse_continue_expr(),
-            TokenKind::Open(Delim::Paren) => self.parse_function(),
-            TokenKind::Open(Delim::Curly) => self.parse_compound_expr(tok).map(Expr::Compound),
             TokenKind::Identifier => {
-                if self.peek()?.kind == TokenKind::ColonEqual {
-                    self.consume()?;
-                    let value = self.parse_statement_expr()?;
+                self.consume()?;
 
-                    let identifier = self.ctx.get_or_intern_str(
-                        &self.ctx.get_source_code()[tok.span.start.0..tok.span.end.0],
-                    );
+                let identifier = self.intern_token(tok);
 
-                    Some(Expr::BindDef(BindDef {
+                if matches!(
+                    self.peek().map(|tok| tok.kind),
+                    Some(TokenKind::UnitConstant | TokenKind::Open(Delim::Paren))
+                ) {
+                    let open_paren_tok = self.consume()?;
+
+                    let arguments = if open_paren_tok.kind == TokenKind::Open(Delim::Paren) {
+                        self.parse_call_arguments(open_paren_tok)?
+                    } else {
+                        vec![]
+                    };
+
+                    ExprKind::FnCall(FnCallExpr {
                         identifier,
-                        value: self.ctx.alloc_expr(value),
-                    }))
-                } else if self.peek()?.kind == TokenKind::Open(Delim::Paren) {
-                    self.consume()?;
+                        identifier_span: tok.span,
+                        arguments: self.ctx.alloc_slice_of_expr(&arguments),
+                    })
+                } else {
+                    ExprKind::BindRef(BindRef { identifier })
+                }
+            }
+            _ => {
+                self.ctx
+                    .report(self.unexpected_token_error("expected an expression"));
 
-                    let close_paren_tok = self.consume()?;
-                    debug_assert_eq!(close_paren_tok.kind, TokenKind::Closed(Delim::Paren));
+                return None;
+            }
+        };
 
-                    let identifier = self.ctx.get_or_intern_str(
-                        &self.ctx.get_source_code()[tok.span.start.0..tok.span.end.0],
-                    );
+        Some(self.make_expr(kind, tok.span.start))
+    }
 
-                    Some(Expr::FnCall(FnCallExpr { identifier }))
-                } else {
-                    let identifier = self.ctx.get_or_intern_str(
-                        &self.ctx.get_source_code()[tok.span.start.0..tok.span.end.0],
-                    );
+    /// Splits an integer constant token into its value and its type suffix,
+    /// such as `255u8`. The digits may follow a `0x`, `0o` or `0b` prefix, and
+    /// be separated by `_`. Whether the value fits its type is left to the
+    /// type checker, which may infer the type.
+    fn parse_integer_constant(&self, tok: Token) -> Option<Const> {
+        let text = self.token_text(tok);
+
+        let (radix, radix_name) = match text.get(..2) {
+            Some("0x") => (16, "a hexadecimal"),
+            Some("0o") => (8, "an octal"),
+            Some("0b") => (2, "a binary"),
+            _ => (10, "a decimal"),
+        };
+        let prefix = if radix == 10 { "" } else { &text[..2] };
+
+        let text = &text[prefix.len()..];
+        let suffix_start = text
+            .find(|ch: char| !ch.is_digit(radix) && ch != '_')
+            .unwrap_or(text.len());
+        let (digits, suffix) = text.split_at(suffix_start);
+        let digits = digits.replace('_', "");
+
+        if let Some(digit) = suffix.chars().next().filter(char::is_ascii_digit) {
+            self.ctx.report(Diagnostic::error(
+                format!("invalid digit `{}` in {} constant", digit, radix_name),
+                tok.span,
+            ));
+
+            return None;
+        }
 
-                    Some(Expr::BindRef(BindRef { identifier }))
-                }
+        if digits.is_empty() {
+            self.ctx.report(Diagnostic::error(
+                format!("expected digits after the `{}` prefix", prefix),
+                tok.span,
+            ));
+
+            return None;
+        }
+
+        if radix == 10 && digits.len() > 1 && digits.starts_with('0') {
+            self.ctx.report(
+                Diagnostic::error(
+                    "leading zeros are not allowed in a decimal constant",
+                    tok.span,
+                )
+                .with_help("use the `0o` prefix for an octal constant"),
+            );
+
+            return None;
+        }
+
+        let suffix = if suffix.is_empty() {
+            None
+        } else {
+            let Some(int_type) = IntType::from_name(suffix) else {
+                self.ctx.report(
+                    Diagnostic::error(
+                        format!("invalid suffix `{}` for an integer constant", suffix),
+                        tok.span,
+                    )
+                    .with_note(
+                        "the valid suffixes are `i8`, `i16`, `i32`, `i64`, `u8`, `u16`, `u32` and \
+                         `u64`",
+                    ),
+                );
+
+                return None;
+            };
+
+            Some(int_type)
+        };
+
+        let Ok(value) = u64::from_str_radix(&digits, radix) else {
+            self.ctx.report(Diagnostic::error(
+                "integer constant is too large for any integer type",
+                tok.span,
+            ));
+
+            return None;
+        };
+
+        Some(Const::IntegerConstant { value, suffix })
+    }
+
+    /// Parses the arguments that follow a call's `(`, up to and including the
+    /// closing `)`. A trailing comma is accepted.
+    fn parse_call_arguments(&mut self, open_paren_tok: Token) -> Option<Vec<Expr<'ctx>>> {
+        let mut arguments = vec![];
+
+        while self.peek().is_some() && !self.is_at(TokenKind::Closed(Delim::Paren)) {
+            arguments.push(self.parse_expr()?);
+
+            if self.is_at(TokenKind::Comma) {
+                self.consume()?;
+            } else {
+                break;
             }
-            _ => None,
+        }
+
+        self.expect_closing(
+            open_paren_tok,
+            TokenKind::Closed(Delim::Paren),
+            "expected `,` or `)` after a call argument",
+        )?;
+
+        Some(arguments)
+    }
+
+    fn parse_paren_expr(&mut self, open_paren_tok: Token) -> Option<Expr<'ctx>> {
+        debug_assert_eq!(open_paren_tok.kind, TokenKind::Open(Delim::Paren));
+
+        // `( )` is the unit constant, just spelled with blanks in between.
+        if self.is_at(TokenKind::Closed(Delim::Paren)) {
+            self.consume()?;
+
+            return Some(self.make_expr(ExprKind::Const(Const::Unit), open_paren_tok.span.start));
+        }
+
+        let expr = self.parse_expr()?;
+
+        self.expect_closing(
+            open_paren_tok,
+            TokenKind::Closed(Delim::Paren),
+            "expected `)` to close the parenthesized expression",
+        )?;
+
+        // Only a function literal can have a return type, so `(x) -> i32` is most
+        // likely a parameter that lacks its type annotation.
+        if self.is_at(TokenKind::DashGreater) {
+            self.ctx.report(
+                self.unexpected_token_error("expected a function literal")
+                    .with_label(
+                        self.span_from(open_paren_tok.span.start),
+                        "this is parsed as a parenthesized expression",
+                    )
+                    .with_note("function parameters are written as `name: type`"),
+            );
+
+            return None;
+        }
+
+        Some(expr)
+    }
+
+    /// Tells whether the upcoming `(` or `()` starts a function literal rather
+    /// than a parenthesized expression or the unit constant. At most three
+    /// tokens are looked at: either a parameter (`identifier :`) follows
+    /// the parenthesis, or an empty parameter list is followed by a return
+    /// type or a body.
+    fn is_at_function_literal(&self) -> bool {
+        let kind_at = |amount| self.look_ahead(amount).map(|tok| tok.kind);
+        let starts_signature_tail = |kind| {
+            matches!(
+                kind,
+                Some(TokenKind::DashGreater | TokenKind::Open(Delim::Curly))
+            )
+        };
+
+        match kind_at(0) {
+            Some(TokenKind::UnitConstant) => starts_signature_tail(kind_at(1)),
+            Some(TokenKind::Open(Delim::Paren)) => match kind_at(1) {
+                Some(TokenKind::Closed(Delim::Paren)) => starts_signature_tail(kind_at(2)),
+                Some(TokenKind::Identifier) => kind_at(2) == Some(TokenKind::Colon),
+                _ => false,
+            },
+            _ => false,
         }
     }
 
     fn parse_expr(&mut self) -> Option<Expr<'ctx>> {
         let stmt_expr = self.parse_statement_expr()?;
 
-        if self.peek()?.kind == TokenKind::Semi {
+        if self.is_at(TokenKind::Semi) {
             self.consume()?;
 
-            Some(Expr::Semi(self.ctx.alloc_expr(stmt_expr)))
+            Some(self.make_expr(
+                ExprKind::Semi(self.ctx.alloc_expr(stmt_expr)),
+                stmt_expr.span.start,
+            ))
         } else {
             Some(stmt_expr)
         }
     }
 
-    fn parse_if_expr(&mut self) -> Option<Expr<'ctx>> {
+    fn parse_if_expr(&mut self, if_tok: Token) -> Option<Expr<'ctx>> {
         let cond_expr = self.parse_expr()?;
 
-        let open_curly_tok = self.consume()?;
-        debug_assert_eq!(open_curly_tok.kind, TokenKind::Open(Delim::Curly));
+        let open_curly_tok = self.expect(
+            TokenKind::Open(Delim::Curly),
+            "expected `{` after the `if` condition",
+        )?;
 
         let true_branch = self.parse_compound_expr(open_curly_tok)?;
 
         let mut else_if_branches = vec![];
 
-        while self.peek()?.kind == TokenKind::Keyword(Keyword::Else) {
-            if self.look_ahead(1)?.kind != TokenKind::Keyword(Keyword::If) {
+        while self.is_at(TokenKind::Keyword(Keyword::Else)) {
+            if !self
+                .look_ahead(1)
+                .is_some_and(|tok| tok.kind == TokenKind::Keyword(Keyword::If))
+            {
                 break;
             }
 
@@ -135,8 +586,10 @@ impl<'ctx> Parser<'ctx> {
 
             let cond_expr = self.parse_expr()?;
 
-            let open_curly_tok = self.consume()?;
-            debug_assert_eq!(open_curly_tok.kind, TokenKind::Open(Delim::Curly));
+            let open_curly_tok = self.expect(
+                TokenKind::Open(Delim::Curly),
+                "expected `{` after the `else if` condition",
+            )?;
 
             let true_branch = self.parse_compound_expr(open_curly_tok)?;
 
@@ -146,11 +599,13 @@ impl<'ctx> Parser<'ctx> {
             });
         }
 
-        let final_branch = if self.peek()?.kind == TokenKind::Keyword(Keyword::Else) {
+        let final_branch = if self.is_at(TokenKind::Keyword(Keyword::Else)) {
             self.consume()?;
 
-            let open_curly_tok = self.consume()?;
-            debug_assert_eq!(open_curly_tok.kind, TokenKind::Open(Delim::Curly));
+            let open_curly_tok = self.expect(
+                TokenKind::Open(Delim::Curly),
+                "expected `{` or `if` after `else`",
+            )?;
 
             let branch = self.parse_compound_expr(open_curly_tok)?;
 
@@ -159,46 +614,70 @@ impl<'ctx> Parser<'ctx> {
             None
         };
 
-        Some(Expr::If(IfExpr {
-            cond_expr: self.ctx.alloc_expr(cond_expr),
-            true_branch,
-            else_if_branches: self.ctx.alloc_slice_of_else_if_branch(&else_if_branches),
-            final_branch,
-        }))
+        Some(self.make_expr(
+            ExprKind::If(IfExpr {
+                cond_expr: self.ctx.alloc_expr(cond_expr),
+                true_branch,
+                else_if_branches: self.ctx.alloc_slice_of_else_if_branch(&else_if_branches),
+                final_branch,
+            }),
+            if_tok.span.start,
+        ))
     }
 
-    fn parse_for_expr(&mut self) -> Option<Expr<'ctx>> {
-        let iteration = if self.peek()?.kind == TokenKind::Identifier
-            && self.look_ahead(1)?.kind == TokenKind::Colon
+    fn parse_for_expr(&mut self, label: Option<Label>, for_tok: Token) -> Option<Expr<'ctx>> {
+        let iteration = if self.is_at(TokenKind::Identifier)
+            && self
+                .look_ahead(1)
+                .is_some_and(|tok| tok.kind == TokenKind::Colon)
         {
             let ident_tok = self.consume()?;
-            let identifier = self.ctx.get_or_intern_str(
-                &self.ctx.get_source_code()[ident_tok.span.start.0..ident_tok.span.end.0],
-            );
+            let identifier = self.intern_token(ident_tok);
 
-            let in_kw_tok = self.consume()?;
-            debug_assert_eq!(in_kw_tok.kind, TokenKind::Colon);
+            self.consume()?;
 
             let start_expr = self.parse_expr()?;
 
-            let range_tok = self.consume()?;
-            let range_kind = if range_tok.kind == TokenKind::PeriodPeriodEqual {
-                RangeKind::Inclusive
-            } else {
-                debug_assert_eq!(range_tok.kind, TokenKind::PeriodPeriod);
+            let range_kind = match self.peek().map(|tok| tok.kind) {
+                Some(TokenKind::PeriodPeriodEqual) => RangeKind::Inclusive,
+                Some(TokenKind::PeriodPeriod) => RangeKind::Exclusive,
+                _ => {
+                    self.ctx.report(
+                        self.unexpected_token_error("expected `..` or `..=` after the range start"),
+                    );
 
-                RangeKind::Exclusive
+                    return None;
+                }
             };
 
+            self.consume()?;
+
             let end_expr = self.parse_expr()?;
 
+            let (step_expr, is_descending) = if self.is_at(TokenKind::Keyword(Keyword::By)) {
+                self.consume()?;
+
+                let is_descending = self.is_at(TokenKind::Dash);
+
+                if is_descending {
+                    self.consume()?;
+                }
+
+                (Some(self.parse_expr()?), is_descending)
+            } else {
+                (None, false)
+            };
+
             Some(ForIteration::Iterative {
                 identifier,
+                identifier_span: ident_tok.span,
                 start_expr: self.ctx.alloc_expr(start_expr),
                 end_expr: self.ctx.alloc_expr(end_expr),
                 range_kind,
+                step_expr: step_expr.map(|step_expr| self.ctx.alloc_expr(step_expr)),
+                is_descending,
             })
-        } else if self.peek()?.kind != TokenKind::Open(Delim::Curly) {
+        } else if !self.is_at(TokenKind::Open(Delim::Curly)) {
             let cond_expr = self.parse_expr()?;
 
             Some(ForIteration::Conditional {
@@ -208,53 +687,207 @@ impl<'ctx> Parser<'ctx> {
             None
         };
 
-        let open_curly_tok = self.consume()?;
-        debug_assert_eq!(open_curly_tok.kind, TokenKind::Open(Delim::Curly));
+        let open_curly_tok = self.expect(
+            TokenKind::Open(Delim::Curly),
+            "expected `{` to start the `for` body",
+        )?;
+
+        if let Some(label) = label {
+            self.enclosing_labels.push(label.identifier);
+        }
 
-        let for_loop_body = self.parse_compound_expr(open_curly_tok)?;
+        let for_loop_body = self.parse_compound_expr(open_curly_tok);
 
-        Some(Expr::For(ForExpr {
-            iteration,
-            body: for_loop_body,
-        }))
+        if label.is_some() {
+            self.enclosing_labels.pop();
+        }
+
+        Some(self.make_expr(
+            ExprKind::For(ForExpr {
+                label,
+                iteration,
+                body: for_loop_body?,
+            }),
+            label.map_or(for_tok.span.start, |label| label.span.start),
+        ))
     }
 
-    fn parse_break_expr(&mut self) -> Option<Expr<'ctx>> {
-        Some(Expr::Break)
+    fn parse_break_expr(&mut self, break_tok: Token) -> Option<Expr<'ctx>> {
+        // A name after `break` could as well be the value to break with, so it's only
+        // taken as a label here if a loop around it has that label. Otherwise, it's
+        // read as a label only once it's found not to be a binding.
+        let label = match self.peek() {
+            Some(tok)
+                if tok.kind == TokenKind::Identifier
+                    && self.enclosing_labels.contains(&self.intern_token(tok)) =>
+            {
+                self.parse_label()
+            }
+            _ => None,
+        };
+
+        let value = if self.is_at_expr_end() {
+            None
+        } else {
+            let value = self.parse_statement_expr()?;
+
+            Some(self.ctx.alloc_expr(value))
+        };
+
+        Some(self.make_expr(
+            ExprKind::Break(BreakExpr { label, value }),
+            break_tok.span.start,
+        ))
     }
 
-    fn parse_continue_expr(&mut self) -> Option<Expr<'ctx>> {
-        Some(Expr::Continue)
+    fn parse_continue_expr(&mut self, continue_tok: Token) -> Option<Expr<'ctx>> {
+        let label = if self.is_at(TokenKind::Identifier) {
+            self.parse_label()
+        } else {
+            None
+        };
+
+        Some(self.make_expr(ExprKind::Continue(label), continue_tok.span.start))
     }
 
-    fn parse_function(&mut self) -> Option<Expr<'ctx>> {
-        let closed_paren = self.consume()?;
-        debug_assert_eq!(closed_paren.kind, TokenKind::Closed(Delim::Paren));
+    fn parse_label(&mut self) -> Option<Label> {
+        let label_tok = self.consume()?;
 
-        let (return_type, open_curly_tok) = if self.peek()?.kind == TokenKind::DashGreater {
-            self.consume()?;
+        Some(Label {
+            identifier: self.intern_token(label_tok),
+            span: label_tok.span,
+        })
+    }
 
-            let type_tok = self.consume()?;
-            debug_assert_eq!(type_tok.kind, TokenKind::Keyword(Keyword::I32));
+    fn parse_return_expr(&mut self, return_tok: Token) -> Option<Expr<'ctx>> {
+        let value = if self.is_at_expr_end() {
+            None
+        } else {
+            let value = self.parse_statement_expr()?;
+
+            Some(self.ctx.alloc_expr(value))
+        };
+
+        Some(self.make_expr(ExprKind::Return(value), return_tok.span.start))
+    }
+
+    /// Tells whether no expression can start at the upcoming token, which is
+    /// how an operand is found to be omitted, as in `return;` or `break;`.
+    fn is_at_expr_end(&self) -> bool {
+        matches!(
+            self.peek().map(|tok| tok.kind),
+            None | Some(
+                TokenKind::Semi
+                    | TokenKind::Comma
+                    | TokenKind::Closed(Delim::Paren)
+                    | TokenKind::Closed(Delim::Curly)
+            )
+        )
+    }
+
+    fn parse_function(&mut self, open_tok: Token) -> Option<Expr<'ctx>> {
+        let parameters = if open_tok.kind == TokenKind::Open(Delim::Paren) {
+            self.parse_function_parameters(open_tok)?
+        } else {
+            debug_assert_eq!(open_tok.kind, TokenKind::UnitConstant);
 
-            let open_curly_tok = self.consume()?;
-            debug_assert_eq!(open_curly_tok.kind, TokenKind::Open(Delim::Curly));
+            vec![]
+        };
 
-            (Type::I32, open_curly_tok)
+        let return_type = if self.is_at(TokenKind::DashGreater) {
+            self.consume()?;
+
+            self.parse_type()?
         } else {
-            let open_curly_tok = self.consume()?;
-            debug_assert_eq!(open_curly_tok.kind, TokenKind::Open(Delim::Curly));
+            Type::Unit
+        };
+
+        let open_curly_tok = self.expect(
+            TokenKind::Open(Delim::Curly),
+            "expected `{` to start the function body",
+        )?;
+
+        // Loops around a function literal don't reach into its body, and neither do
+        // their labels.
+        let outer_labels = std::mem::take(&mut self.enclosing_labels);
+        let compound_expr = self.parse_compound_expr(open_curly_tok);
+        self.enclosing_labels = outer_labels;
+
+        let compound_expr = compound_expr?;
+
+        Some(self.make_expr(
+            ExprKind::Function(Function {
+                return_type,
+                parameters: self.ctx.alloc_slice_of_param(&parameters),
+                body: compound_expr,
+            }),
+            open_tok.span.start,
+        ))
+    }
+
+    /// Parses the parameters that follow a function literal's `(`, up to and
+    /// including the closing `)`. A trailing comma is accepted.
+    fn parse_function_parameters(&mut self, open_paren_tok: Token) -> Option<Vec<Param>> {
+        let mut parameters = vec![];
+
+        while self.peek().is_some() && !self.is_at(TokenKind::Closed(Delim::Paren)) {
+            let ident_tok = self.expect(TokenKind::Identifier, "expected a parameter name")?;
 
-            (Type::Unit, open_curly_tok)
+            self.expect(
+                TokenKind::Colon,
+                "expected `:` followed by the parameter's type",
+            )?;
+
+            let ty = self.parse_type()?;
+
+            parameters.push(Param {
+                identifier: self.intern_token(ident_tok),
+                identifier_span: ident_tok.span,
+                ty,
+            });
+
+            if self.is_at(TokenKind::Comma) {
+                self.consume()?;
+            } else {
+                break;
+            }
+        }
+
+        self.expect_closing(
+            open_paren_tok,
+            TokenKind::Closed(Delim::Paren),
+            "expected `,` or `)` after a function parameter",
+        )?;
+
+        Some(parameters)
+    }
+
+    fn parse_type(&mut self) -> Option<Type> {
+        let ty = match self.peek().map(|tok| tok.kind) {
+            Some(TokenKind::Keyword(Keyword::I8)) => Type::Int(IntType::I8),
+            Some(TokenKind::Keyword(Keyword::I16)) => Type::Int(IntType::I16),
+            Some(TokenKind::Keyword(Keyword::I32)) => Type::Int(IntType::I32),
+            Some(TokenKind::Keyword(Keyword::I64)) => Type::Int(IntType::I64),
+            Some(TokenKind::Keyword(Keyword::U8)) => Type::Int(IntType::U8),
+            Some(TokenKind::Keyword(Keyword::U16)) => Type::Int(IntType::U16),
+            Some(TokenKind::Keyword(Keyword::U32)) => Type::Int(IntType::U32),
+            Some(TokenKind::Keyword(Keyword::U64)) => Type::Int(IntType::U64),
+            Some(TokenKind::Keyword(Keyword::Bool)) => Type::Bool,
+            Some(TokenKind::UnitConstant) => Type::Unit,
+            _ => {
+                self.ctx
+                    .report(self.unexpected_token_error("expected a type").with_note(
+                        "the available types are `()`, `bool`, `i8`, `i16`, `i32`, `i64`, \
+                         `u8`, `u16`, `u32` and `u64`",
+                    ));
+
+                return None;
+            }
         };
 
-        let compound_expr = self.parse_compound_expr(open_curly_tok)?;
+        self.consume()?;
 
-        Some(Expr::Function(Function {
-            return_type,
-            parameters: self.ctx.alloc_slice_of_param(&[]),
-            body: compound_expr,
-        }))
+        Some(ty)
     }
 
     fn parse_compound_expr(&mut self, open_curly_tok: Token) -> Option<CompoundExpr<'ctx>> {
@@ -262,19 +895,210 @@ impl<'ctx> Parser<'ctx> {
 
         let mut exprs = vec![];
 
-        while self.peek()?.kind != TokenKind::Closed(Delim::Curly) {
-            let expr = self.parse_expr()?;
-            exprs.push(expr);
+        // A declaration can't be inside a block, so one starting means that the block's
+        // `}` is missing. That's not the case for `mut identifier :=`, which is also
+        // how a mutable binding starts.
+        while self.peek().is_some()
+            && !self.is_at(TokenKind::Closed(Delim::Curly))
+            && !self.is_at_constant_decl_start()
+        {
+            let expr_start = self.peek()?.span.start;
+
+            match self.parse_expr() {
+                Some(expr) => exprs.push(expr),
+                None => {
+                    exprs.push(self.make_expr(ExprKind::Error, expr_start));
+
+                    if !self.synchronize_to_block_end() {
+                        return None;
+                    }
+                }
+            }
         }
 
-        let closed_curly_tok = self.consume()?;
-        debug_assert_eq!(closed_curly_tok.kind, TokenKind::Closed(Delim::Curly));
+        self.expect_closing(
+            open_curly_tok,
+            TokenKind::Closed(Delim::Curly),
+            "expected `}` to close the block",
+        )?;
 
         Some(CompoundExpr {
             exprs: self.ctx.alloc_slice_of_expr(&exprs),
         })
     }
 
+    /// Skips tokens up to the next declaration start, either `identifier ::` or
+    /// `mut identifier :=`.
+    fn synchronize_to_decl(&mut self) {
+        while self.peek().is_some() && !self.is_at_decl_start() {
+            self.consume();
+        }
+    }
+
+    /// Skips tokens up to the `}` that closes the current block, which is left
+    /// to be consumed. Returns whether it was found before the end of the
+    /// file or the next declaration.
+    fn synchronize_to_block_end(&mut self) -> bool {
+        let mut nested_block_depth = 0;
+
+        while let Some(tok) = self.peek() {
+            match tok.kind {
+                TokenKind::Open(Delim::Curly) => nested_block_depth += 1,
+                TokenKind::Closed(Delim::Curly) if nested_block_depth == 0 => return true,
+                TokenKind::Closed(Delim::Curly) => nested_block_depth -= 1,
+                TokenKind::Identifier if self.is_at_constant_decl_start() => return false,
+                _ => {}
+            }
+
+            self.consume();
+        }
+
+        false
+    }
+
+    fn is_at_decl_start(&self) -> bool {
+        self.is_at_constant_decl_start() || self.is_at_global_decl_start()
+    }
+
+    fn is_at_constant_decl_start(&self) -> bool {
+        self.is_at(TokenKind::Identifier)
+            && self
+                .look_ahead(1)
+                .is_some_and(|tok| tok.kind == TokenKind::ColonColon)
+    }
+
+    fn is_at_global_decl_start(&self) -> bool {
+        self.is_at(TokenKind::Keyword(Keyword::Mut))
+            && self
+                .look_ahead(1)
+                .is_some_and(|tok| tok.kind == TokenKind::Identifier)
+            && self
+                .look_ahead(2)
+                .is_some_and(|tok| tok.kind == TokenKind::ColonEqual)
+    }
+
+    /// Takes the doc comments that come before `pos`. The ones right before it
+    /// are returned, while those that come before the last consumed token
+    /// document nothing, and are warned about.
+    fn take_doc_comments_before(&mut self, pos: BytePos) -> Vec<Span> {
+        let prev_token_end = self
+            .current_token_idx
+            .checked_sub(1)
+            .map_or(BytePos(0), |prev_token_idx| {
+                self.tokens[prev_token_idx].span.end
+            });
+
+        let mut doc_comments = vec![];
+
+        while let Some(&doc_comment) = self.doc_comments.get(self.next_doc_comment_idx) {
+            if doc_comment.span.start >= pos {
+                break;
+            }
+
+            if doc_comment.span.start >= prev_token_end {
+                doc_comments.push(doc_comment.span);
+            } else {
+                self.warn_unused_doc_comment(doc_comment.span);
+            }
+
+            self.next_doc_comment_idx += 1;
+        }
+
+        doc_comments
+    }
+
+    fn warn_unused_doc_comment(&self, span: Span) {
+        self.ctx.report(
+            Diagnostic::warning("unused doc comment", span)
+                .with_note("doc comments only document the declaration that follows them"),
+        );
+    }
+
+    fn binary_op_of(token_kind: TokenKind) -> Option<BinaryOp> {
+        let op = match token_kind {
+            TokenKind::Star => BinaryOp::Mul,
+            TokenKind::Slash => BinaryOp::Div,
+            TokenKind::Plus => BinaryOp::Add,
+            TokenKind::Dash => BinaryOp::Sub,
+            TokenKind::LessLess => BinaryOp::Shl,
+            TokenKind::GreaterGreater => BinaryOp::Shr,
+            TokenKind::Less => BinaryOp::Less,
+            TokenKind::Greater => BinaryOp::Greater,
+            TokenKind::LessEqual => BinaryOp::LessEqual,
+            TokenKind::GreaterEqual => BinaryOp::GreaterEqual,
+            TokenKind::EqualEqual => BinaryOp::Equal,
+            TokenKind::ExclaEqual => BinaryOp::NotEqual,
+            TokenKind::AmpAmp => BinaryOp::LogicalAnd,
+            TokenKind::PipePipe => BinaryOp::LogicalOr,
+            _ => return None,
+        };
+
+        Some(op)
+    }
+
+    /// Finds what an assignment operator does with the binding's value: `=`
+    /// just replaces it, while `+=` and the like apply a binary operator.
+    fn assignment_op_of(token_kind: TokenKind) -> Option<Option<BinaryOp>> {
+        let op = match token_kind {
+            TokenKind::Equal => None,
+            TokenKind::PlusEqual => Some(BinaryOp::Add),
+            TokenKind::DashEqual => Some(BinaryOp::Sub),
+            TokenKind::StarEqual => Some(BinaryOp::Mul),
+            TokenKind::SlashEqual => Some(BinaryOp::Div),
+            TokenKind::LessLessEqual => Some(BinaryOp::Shl),
+            TokenKind::GreaterGreaterEqual => Some(BinaryOp::Shr),
+            _ => return None,
+        };
+
+        Some(op)
+    }
+
+    fn precedence_of(op: BinaryOp) -> u8 {
+        match op {
+            BinaryOp::Mul | BinaryOp::Div => Parser::PRODUCT_PRECEDENCE,
+            BinaryOp::Add | BinaryOp::Sub => Parser::FACTOR_PRECEDENCE,
+            BinaryOp::Shl | BinaryOp::Shr => Parser::SHIFT_PRECEDENCE,
+            BinaryOp::Less | BinaryOp::Greater | BinaryOp::LessEqual | BinaryOp::GreaterEqual => {
+                Parser::RELATIONAL_PRECEDENCE
+            }
+            BinaryOp::Equal | BinaryOp::NotEqual => Parser::EQUALITY_PRECEDENCE,
+            BinaryOp::LogicalAnd => Parser::LOGICAL_AND_PRECEDENCE,
+            BinaryOp::LogicalOr => Parser::LOGICAL_OR_PRECEDENCE,
+        }
+    }
+
+    fn make_expr(&mut self, kind: ExprKind<'ctx>, start: BytePos) -> Expr<'ctx> {
+        let id = NodeId(self.next_node_id);
+        self.next_node_id += 1;
+
+        Expr {
+            id,
+            kind,
+            span: self.span_from(start),
+        }
+    }
+
+    /// Makes a span that goes from `start` to the end of the last consumed
+    /// token, or an empty one if no token was consumed since `start`.
+    fn span_from(&self, start: BytePos) -> Span {
+        let end = self
+            .current_token_idx
+            .checked_sub(1)
+            .map(|prev_token_idx| self.tokens[prev_token_idx].span.end)
+            .filter(|&end| end >= start)
+            .unwrap_or(start);
+
+        Span { start, end }
+    }
+
+    fn token_text(&self, tok: Token) -> &'ctx str {
+        &self.ctx.get_source_code()[tok.span.start.0..tok.span.end.0]
+    }
+
+    fn intern_token(&self, tok: Token) -> Symbol {
+        self.ctx.get_or_intern_str(self.token_text(tok))
+    }
+
     fn peek(&self) -> Option<Token> {
         if self.current_token_idx < self.tokens.len() {
             Some(self.tokens[self.current_token_idx])
@@ -293,6 +1117,60 @@ impl<'ctx> Parser<'ctx> {
         }
     }
 
+    fn is_at(&self, kind: TokenKind) -> bool {
+        self.peek().is_some_and(|tok| tok.kind == kind)
+    }
+
+    fn expect(&mut self, expected_kind: TokenKind, message: &str) -> Option<Token> {
+        if self.is_at(expected_kind) {
+            self.consume()
+        } else {
+            self.ctx.report(self.unexpected_token_error(message));
+
+            None
+        }
+    }
+
+    /// Like `expect`, but for the delimiter that closes `open_tok`, which gets
+    /// pointed at when the closing one is missing.
+    fn expect_closing(
+        &mut self,
+        open_tok: Token,
+        expected_kind: TokenKind,
+        message: &str,
+    ) -> Option<Token> {
+        if self.is_at(expected_kind) {
+            self.consume()
+        } else {
+            self.ctx.report(
+                self.unexpected_token_error(message)
+                    .with_label(open_tok.span, "unclosed delimiter"),
+            );
+
+            None
+        }
+    }
+
+    /// Makes an error about the upcoming token, which is not what `message`
+    /// expected.
+    fn unexpected_token_error(&self, message: &str) -> Diagnostic {
+        match self.peek() {
+            Some(tok) => Diagnostic::error(
+                format!("{}, found `{}`", message, self.token_text(tok)),
+                tok.span,
+            ),
+            None => {
+                let source_code_len = self.ctx.get_source_code().len();
+                let eof_span = Span {
+                    start: BytePos(source_code_len),
+                    end: BytePos(source_code_len),
+                };
+
+                Diagnostic::error(format!("{}, found end of file", message), eof_span)
+            }
+        }
+    }
+
     fn consume(&mut self) -> Option<Token> {
         let peeked_tok = self.peek();
 
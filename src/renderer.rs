@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::compiler_context::CompilerContext;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::scanner::Span;
+
+/// Renders diagnostics the way rustc does: a header with the message, the
+/// location of the primary span, and the source lines involved, with the spans
+/// underlined.
+pub(crate) struct Renderer<'ctx> {
+    ctx: &'ctx CompilerContext,
+    file_name: &'ctx str,
+    use_color: bool,
+}
+
+/// A span to underline on a source line, with an optional message next to the
+/// underline.
+struct Annotation<'d> {
+    span: Span,
+    message: Option<&'d str>,
+    is_primary: bool,
+}
+
+#[derive(Clone, Copy)]
+enum Style {
+    Error,
+    Warning,
+    Gutter,
+    Emphasis,
+}
+
+impl<'ctx> Renderer<'ctx> {
+    pub(crate) fn new(
+        ctx: &'ctx CompilerContext,
+        file_name: &'ctx str,
+        use_color: bool,
+    ) -> Renderer<'ctx> {
+        Renderer {
+            ctx,
+            file_name,
+            use_color,
+        }
+    }
+
+    pub(crate) fn render(&self, diagnostic: &Diagnostic) -> String {
+        let mut rendered = String::new();
+
+        let (severity_name, severity_style) = match diagnostic.severity {
+            Severity::Error => ("error", Style::Error),
+            Severity::Warning => ("warning", Style::Warning),
+        };
+
+        let mut annotations = vec![Annotation {
+            span: diagnostic.primary_span,
+            message: None,
+            is_primary: true,
+        }];
+
+        annotations.extend(diagnostic.labels.iter().map(|label| Annotation {
+            span: label.span,
+            message: Some(label.message.as_str()),
+            is_primary: false,
+        }));
+
+        let mut annotations_by_line = BTreeMap::<usize, Vec<Annotation>>::new();
+
+        for annotation in annotations {
+            let line = self.ctx.get_location(annotation.span.start).line;
+            annotations_by_line
+                .entry(line)
+                .or_default()
+                .push(annotation);
+        }
+
+        let max_line = *annotations_by_line.keys().next_back().unwrap();
+        let gutter_padding = " ".repeat(max_line.to_string().len());
+        let empty_gutter = self.paint(&format!("{} |", gutter_padding), Style::Gutter);
+
+        let primary_location = self.ctx.get_location(diagnostic.primary_span.start);
+
+        writeln!(
+            rendered,
+            "{}{}",
+            self.paint(severity_name, severity_style),
+            self.paint(&format!(": {}", diagnostic.message), Style::Emphasis)
+        )
+        .unwrap();
+        writeln!(
+            rendered,
+            "{}{} {}:{}:{}",
+            gutter_padding,
+            self.paint("-->", Style::Gutter),
+            self.file_name,
+            primary_location.line,
+            primary_location.column
+        )
+        .unwrap();
+        writeln!(rendered, "{}", empty_gutter).unwrap();
+
+        let mut previous_line = None;
+
+        for (line, mut annotations) in annotations_by_line {
+            annotations.sort_by_key(|annotation| annotation.span.start.0);
+
+            if previous_line.is_some_and(|previous_line| line > previous_line + 1) {
+                writeln!(rendered, "{}", self.paint("...", Style::Gutter)).unwrap();
+            }
+
+            let line_text = self.ctx.get_line_text(line);
+            let line_gutter = self.paint(
+                &format!("{:>width$} |", line, width = gutter_padding.len()),
+                Style::Gutter,
+            );
+
+            writeln!(rendered, "{}", join_non_empty(&line_gutter, line_text)).unwrap();
+
+            for annotation in annotations {
+                let underline = self.render_underline(&annotation, line, line_text);
+                let underline = if annotation.is_primary {
+                    self.paint(&underline, severity_style)
+                } else {
+                    self.paint(&underline, Style::Gutter)
+                };
+
+                writeln!(rendered, "{} {}", empty_gutter, underline).unwrap();
+            }
+
+            previous_line = Some(line);
+        }
+
+        let notes = diagnostic.notes.iter().map(|note| ("note:", note));
+        let helps = diagnostic.helps.iter().map(|help| ("help:", help));
+
+        for (kind, text) in notes.chain(helps) {
+            writeln!(
+                rendered,
+                "{} {} {}",
+                gutter_padding,
+                self.paint("=", Style::Gutter),
+                join_non_empty(&self.paint(kind, Style::Emphasis), text)
+            )
+            .unwrap();
+        }
+
+        rendered
+    }
+
+    /// Underlines the part of the annotation's span that is in `line`, which is
+    /// where the span starts. Spans that go on to later lines are
+    /// underlined up to the line's end.
+    fn render_underline(&self, annotation: &Annotation, line: usize, line_text: &str) -> String {
+        let start = self.ctx.get_location(annotation.span.start);
+        let end = self.ctx.get_location(annotation.span.end);
+
+        let end_column = if end.line == line {
+            end.column
+        } else {
+            line_text.chars().count() + 1
+        };
+
+        let marker = if annotation.is_primary { "^" } else { "-" };
+        let underline = format!(
+            "{}{}",
+            " ".repeat(start.column - 1),
+            marker.repeat(end_column.saturating_sub(start.column).max(1))
+        );
+
+        match annotation.message {
+            Some(message) => format!("{} {}", underline, message),
+            None => underline,
+        }
+    }
+
+    fn paint(&self, text: &str, style: Style) -> String {
+        if !self.use_color {
+            return text.to_owned();
+        }
+
+        let ansi_code = match style {
+            Style::Error => "1;31",
+            Style::Warning => "1;33",
+            Style::Gutter => "1;34",
+            Style::Emphasis => "1",
+        };
+
+        format!("\x1b[{}m{}\x1b[0m", ansi_code, text)
+    }
+}
+
+/// Joins a prefix and some text with a space, leaving no trailing blank if the
+/// text is empty.
+fn join_non_empty(prefix: &str, text: &str) -> String {
+    if text.is_empty() {
+        prefix.to_owned()
+    } else {
+        format!("{} {}", prefix, text)
+    }
+}
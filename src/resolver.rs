@@ -0,0 +1,364 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{
+    BreakExpr, CompoundExpr, Expr, ExprKind, ForIteration, Function, NodeId, Program,
+};
+use crate::compiler_context::CompilerContext;
+use crate::diagnostic::Diagnostic;
+use crate::interner::Symbol;
+use crate::scanner::Span;
+
+/// What a name refers to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Definition {
+    /// A top-level declaration, such as a function.
+    TopLevel(Symbol),
+    /// A local binding, made by the `BindDef` or iterative `for` expression
+    /// with this id.
+    Local(NodeId),
+    /// The parameter at `index` of the function literal with this id.
+    Param { function: NodeId, index: usize },
+}
+
+/// The definitions that names resolve to, keyed by the id of the `BindRef`,
+/// `Assign` or `FnCall` expression that uses them.
+#[derive(Default)]
+pub(crate) struct NameResolution {
+    definition_by_node: HashMap<NodeId, Definition>,
+}
+
+impl NameResolution {
+    pub(crate) fn get(&self, node_id: NodeId) -> Option<Definition> {
+        self.definition_by_node.get(&node_id).copied()
+    }
+}
+
+/// Resolves every name in the program to its definition, following the scoping
+/// rules:
+///
+/// - Top-level declarations are visible everywhere, regardless of their order,
+///   and their names must be unique. Only the ones that are functions can be
+///   called.
+/// - Bindings, parameters and loop variables are visible from their definition
+///   to the end of their block, and may shadow names from an outer block, but
+///   not from the same one.
+/// - Function literals don't see the bindings around them.
+/// - Only bindings defined with `mut` can be assigned to.
+pub(crate) struct Resolver<'ctx> {
+    ctx: &'ctx CompilerContext,
+    top_level_span_by_symbol: HashMap<Symbol, Span>,
+    /// Names of the top-level declarations that are functions, rather than
+    /// constants or globals.
+    top_level_functions: HashSet<Symbol>,
+    scope_stack: Vec<Scope>,
+    mutable_definitions: HashSet<Definition>,
+    resolution: NameResolution,
+}
+
+#[derive(Default)]
+struct Scope {
+    /// Maps each name to its definition and the span of the name where it is
+    /// defined.
+    definition_by_symbol: HashMap<Symbol, (Definition, Span)>,
+}
+
+impl<'ctx> Resolver<'ctx> {
+    pub(crate) fn new(ctx: &'ctx CompilerContext) -> Resolver<'ctx> {
+        Resolver {
+            ctx,
+            top_level_span_by_symbol: HashMap::new(),
+            top_level_functions: HashSet::new(),
+            scope_stack: vec![],
+            mutable_definitions: HashSet::new(),
+            resolution: NameResolution::default(),
+        }
+    }
+
+    pub(crate) fn resolve_program(&mut self, program: Program) -> NameResolution {
+        for decl in program.decls {
+            if let Some(&previous_span) = self.top_level_span_by_symbol.get(&decl.identifier) {
+                self.report_redefinition(decl.identifier, decl.identifier_span, previous_span);
+            } else {
+                self.top_level_span_by_symbol
+                    .insert(decl.identifier, decl.identifier_span);
+
+                // A declaration whose value failed to parse may well be a function, so
+                // calls to it aren't reported.
+                if let ExprKind::Function(_) | ExprKind::Error = decl.value.kind {
+                    self.top_level_functions.insert(decl.identifier);
+                }
+
+                if decl.is_mutable {
+                    self.mutable_definitions
+                        .insert(Definition::TopLevel(decl.identifier));
+                }
+            }
+        }
+
+        for decl in program.decls {
+            self.resolve_expr(decl.value);
+        }
+
+        std::mem::take(&mut self.resolution)
+    }
+
+    fn resolve_expr(&mut self, expr: &Expr) {
+        match expr.kind {
+            ExprKind::Const(_) | ExprKind::Continue(_) | ExprKind::Error => {}
+            ExprKind::BindRef(bind_ref) => match self.lookup(bind_ref.identifier) {
+                Some(definition) => self.record(expr.id, definition),
+                None => self.ctx.report(Diagnostic::error(
+                    format!(
+                        "cannot find binding `{}` in this scope",
+                        self.ctx.resolve_symbol(bind_ref.identifier)
+                    ),
+                    expr.span,
+                )),
+            },
+            ExprKind::BindDef(bind_def) => {
+                self.resolve_expr(bind_def.value);
+                self.define(
+                    bind_def.identifier,
+                    bind_def.identifier_span,
+                    Definition::Local(expr.id),
+                );
+
+                if bind_def.is_mutable {
+                    self.mutable_definitions.insert(Definition::Local(expr.id));
+                }
+            }
+            ExprKind::Assign(assign_expr) => {
+                self.resolve_expr(assign_expr.value);
+
+                let name = self.ctx.resolve_symbol(assign_expr.identifier);
+
+                match self.lookup_with_span(assign_expr.identifier) {
+                    Some((definition, _)) if self.mutable_definitions.contains(&definition) => {
+                        self.record(expr.id, definition);
+                    }
+                    // The assignment is left unresolved, so that its type isn't checked
+                    // against a binding it can't change.
+                    Some((_, definition_span)) => self.ctx.report(
+                        Diagnostic::error(
+                            format!("cannot assign to `{}`, as it is not mutable", name),
+                            expr.span,
+                        )
+                        .with_label(definition_span, format!("`{}` is defined here", name))
+                        .with_note("only bindings defined with `mut` can be assigned to"),
+                    ),
+                    None => self.ctx.report(Diagnostic::error(
+                        format!("cannot find binding `{}` in this scope", name),
+                        assign_expr.identifier_span,
+                    )),
+                }
+            }
+            ExprKind::Function(function) => self.resolve_function(expr.id, function),
+            ExprKind::If(if_expr) => {
+                self.resolve_expr(if_expr.cond_expr);
+                self.resolve_compound_expr(if_expr.true_branch);
+
+                for branch in if_expr.else_if_branches {
+                    self.resolve_expr(branch.cond_expr);
+                    self.resolve_compound_expr(branch.true_branch);
+                }
+
+                if let Some(final_branch) = if_expr.final_branch {
+                    self.resolve_compound_expr(final_branch);
+                }
+            }
+            ExprKind::For(for_expr) => {
+                self.enter_scope();
+
+                match for_expr.iteration {
+                    Some(ForIteration::Conditional { cond_expr }) => self.resolve_expr(cond_expr),
+                    Some(ForIteration::Iterative {
+                        identifier,
+                        identifier_span,
+                        start_expr,
+                        end_expr,
+                        step_expr,
+                        ..
+                    }) => {
+                        self.resolve_expr(start_expr);
+                        self.resolve_expr(end_expr);
+
+                        if let Some(step_expr) = step_expr {
+                            self.resolve_expr(step_expr);
+                        }
+
+                        self.define(identifier, identifier_span, Definition::Local(expr.id));
+                    }
+                    None => {}
+                }
+
+                self.resolve_compound_expr(for_expr.body);
+
+                self.exit_scope();
+            }
+            // A name after `break` that isn't a binding is taken as a label, which is
+            // left to the label resolver to report.
+            ExprKind::Break(break_expr)
+                if break_expr
+                    .value_as_label()
+                    .is_some_and(|label| self.lookup(label.identifier).is_none()) => {}
+            ExprKind::Break(BreakExpr { value, .. }) | ExprKind::Return(value) => {
+                if let Some(value) = value {
+                    self.resolve_expr(value);
+                }
+            }
+            ExprKind::Compound(compound_expr) => self.resolve_compound_expr(compound_expr),
+            ExprKind::Semi(expr) => self.resolve_expr(expr),
+            ExprKind::FnCall(fn_call_expr) => {
+                for argument in fn_call_expr.arguments {
+                    self.resolve_expr(argument);
+                }
+
+                let name = self.ctx.resolve_symbol(fn_call_expr.identifier);
+
+                // Only top-level functions can be called, so bindings are not looked at.
+                if self.top_level_functions.contains(&fn_call_expr.identifier) {
+                    self.record(expr.id, Definition::TopLevel(fn_call_expr.identifier));
+                } else if let Some(&decl_span) =
+                    self.top_level_span_by_symbol.get(&fn_call_expr.identifier)
+                {
+                    self.ctx.report(
+                        Diagnostic::error(
+                            format!("`{}` is not a function", name),
+                            fn_call_expr.identifier_span,
+                        )
+                        .with_label(decl_span, format!("`{}` is declared here", name)),
+                    );
+                } else {
+                    self.ctx.report(Diagnostic::error(
+                        format!("cannot find function `{}` in this scope", name),
+                        fn_call_expr.identifier_span,
+                    ));
+                }
+            }
+            ExprKind::Binary(binary_expr) => {
+                self.resolve_expr(binary_expr.lhs);
+                self.resolve_expr(binary_expr.rhs);
+            }
+            ExprKind::Unary(unary_expr) => self.resolve_expr(unary_expr.operand),
+            ExprKind::Cast(cast_expr) => self.resolve_expr(cast_expr.operand),
+        }
+    }
+
+    fn resolve_function(&mut self, function_id: NodeId, function: Function) {
+        // Function literals can't capture the bindings around them.
+        let outer_scope_stack = std::mem::take(&mut self.scope_stack);
+
+        self.enter_scope();
+
+        for (index, param) in function.parameters.iter().enumerate() {
+            self.define(
+                param.identifier,
+                param.identifier_span,
+                Definition::Param {
+                    function: function_id,
+                    index,
+                },
+            );
+        }
+
+        self.resolve_compound_expr(function.body);
+
+        self.exit_scope();
+
+        self.scope_stack = outer_scope_stack;
+    }
+
+    fn resolve_compound_expr(&mut self, compound_expr: CompoundExpr) {
+        self.enter_scope();
+
+        for expr in compound_expr.exprs {
+            self.resolve_expr(expr);
+        }
+
+        self.exit_scope();
+    }
+
+    fn define(&mut self, identifier: Symbol, identifier_span: Span, definition: Definition) {
+        let this_scope = self.scope_stack.last().unwrap();
+
+        if let Some(&(_, previous_span)) = this_scope.definition_by_symbol.get(&identifier) {
+            self.report_redefinition(identifier, identifier_span, previous_span);
+
+            return;
+        }
+
+        if self.lookup_local(identifier).is_none() {
+            if let Some(&decl_span) = self.top_level_span_by_symbol.get(&identifier) {
+                let name = self.ctx.resolve_symbol(identifier);
+
+                self.ctx.report(
+                    Diagnostic::warning(
+                        format!(
+                            "`{}` shadows the top-level declaration of the same name",
+                            name
+                        ),
+                        identifier_span,
+                    )
+                    .with_label(decl_span, format!("`{}` is declared here", name)),
+                );
+            }
+        }
+
+        self.scope_stack
+            .last_mut()
+            .unwrap()
+            .definition_by_symbol
+            .insert(identifier, (definition, identifier_span));
+    }
+
+    fn lookup(&self, identifier: Symbol) -> Option<Definition> {
+        self.lookup_with_span(identifier)
+            .map(|(definition, _)| definition)
+    }
+
+    /// Finds the definition of a name, along with the span of the name where
+    /// it is defined.
+    fn lookup_with_span(&self, identifier: Symbol) -> Option<(Definition, Span)> {
+        self.lookup_local(identifier).or_else(|| {
+            self.top_level_span_by_symbol
+                .get(&identifier)
+                .map(|&span| (Definition::TopLevel(identifier), span))
+        })
+    }
+
+    fn lookup_local(&self, identifier: Symbol) -> Option<(Definition, Span)> {
+        self.scope_stack
+            .iter()
+            .rev()
+            .find_map(|scope| scope.definition_by_symbol.get(&identifier).copied())
+    }
+
+    fn record(&mut self, node_id: NodeId, definition: Definition) {
+        self.resolution
+            .definition_by_node
+            .insert(node_id, definition);
+    }
+
+    fn report_redefinition(&self, identifier: Symbol, span: Span, previous_span: Span) {
+        let name = self.ctx.resolve_symbol(identifier);
+
+        self.ctx.report(
+            Diagnostic::error(
+                format!("the name `{}` is defined multiple times", name),
+                span,
+            )
+            .with_label(
+                previous_span,
+                format!("previous definition of `{}` here", name),
+            ),
+        );
+    }
+
+    fn enter_scope(&mut self) {
+        self.scope_stack.push(Scope::default());
+    }
+
+    fn exit_scope(&mut self) {
+        self.scope_stack.pop();
+    }
+}
@@ -2,6 +2,7 @@ use std::iter::Peekable;
 use std::str::Chars;
 
 use crate::compiler_context::CompilerContext;
+use crate::diagnostic::Diagnostic;
 
 pub(crate) struct Scanner<'ctx> {
     ctx: &'ctx CompilerContext,
@@ -31,60 +32,193 @@ impl Scanner<'_> {
     }
 
     fn scan_next_token(&mut self) -> Option<Token> {
-        self.skip_whitespace();
+        // Comments are skipped, as are characters that don't start any token
+        // after being reported.
+        loop {
+            self.skip_whitespace();
+
+            let span_start = self.current_peek_pos;
+
+            let token_kind = match self.bump() {
+                Scanner::EOF_CHAR => return None,
+                ';' => TokenKind::Semi,
+                ',' => TokenKind::Comma,
+                '*' if self.peek() == '=' => {
+                    self.bump();
+
+                    TokenKind::StarEqual
+                }
+                '*' => TokenKind::Star,
+                '/' if self.peek() == '/' => {
+                    self.bump();
+
+                    // `///` starts a doc comment, but `////` doesn't, so that
+                    // rulers made of slashes stay plain comments.
+                    let is_doc_comment = self.peek() == '/' && self.peek_second() != '/';
+
+                    self.skip_line();
+
+                    if !is_doc_comment {
+                        continue;
+                    }
 
-        let span_start = self.current_peek_pos;
+                    TokenKind::DocComment
+                }
+                '/' if self.peek() == '*' => {
+                    self.bump();
+                    self.skip_block_comment(span_start);
 
-        let token_kind = match self.bump() {
-            Scanner::EOF_CHAR => return None,
-            ';' => TokenKind::Semi,
-            ':' => {
-                if self.peek() == ':' {
+                    continue;
+                }
+                '/' if self.peek() == '=' => {
                     self.bump();
 
-                    TokenKind::ColonColon
-                } else if self.peek() == '=' {
+                    TokenKind::SlashEqual
+                }
+                '/' => TokenKind::Slash,
+                '+' if self.peek() == '=' => {
                     self.bump();
 
-                    TokenKind::ColonEqual
-                } else {
-                    TokenKind::Colon
+                    TokenKind::PlusEqual
                 }
-            }
-            '(' => TokenKind::Open(Delim::Paren),
-            ')' => TokenKind::Closed(Delim::Paren),
-            '{' => TokenKind::Open(Delim::Curly),
-            '}' => TokenKind::Closed(Delim::Curly),
-            '-' if self.peek() == '>' => {
-                self.bump();
-
-                TokenKind::DashGreater
-            }
-            '.' if self.peek() == '.' => {
-                self.bump();
+                '+' => TokenKind::Plus,
+                '!' => {
+                    if self.peek() == '=' {
+                        self.bump();
+
+                        TokenKind::ExclaEqual
+                    } else {
+                        TokenKind::Excla
+                    }
+                }
+                '=' => {
+                    if self.peek() == '=' {
+                        self.bump();
+
+                        TokenKind::EqualEqual
+                    } else {
+                        TokenKind::Equal
+                    }
+                }
+                '<' => {
+                    if self.peek() == '<' {
+                        self.bump();
+
+                        if self.peek() == '=' {
+                            self.bump();
+
+                            TokenKind::LessLessEqual
+                        } else {
+                            TokenKind::LessLess
+                        }
+                    } else if self.peek() == '=' {
+                        self.bump();
+
+                        TokenKind::LessEqual
+                    } else {
+                        TokenKind::Less
+                    }
+                }
+                '>' => {
+                    if self.peek() == '>' {
+                        self.bump();
+
+                        if self.peek() == '=' {
+                            self.bump();
+
+                            TokenKind::GreaterGreaterEqual
+                        } else {
+                            TokenKind::GreaterGreater
+                        }
+                    } else if self.peek() == '=' {
+                        self.bump();
+
+                        TokenKind::GreaterEqual
+                    } else {
+                        TokenKind::Greater
+                    }
+                }
+                ':' => {
+                    if self.peek() == ':' {
+                        self.bump();
+
+                        TokenKind::ColonColon
+                    } else if self.peek() == '=' {
+                        self.bump();
+
+                        TokenKind::ColonEqual
+                    } else {
+                        TokenKind::Colon
+                    }
+                }
+                '(' if self.peek() == ')' => {
+                    self.bump();
+
+                    TokenKind::UnitConstant
+                }
+                '(' => TokenKind::Open(Delim::Paren),
+                ')' => TokenKind::Closed(Delim::Paren),
+                '{' => TokenKind::Open(Delim::Curly),
+                '}' => TokenKind::Closed(Delim::Curly),
+                '-' => {
+                    if self.peek() == '>' {
+                        self.bump();
+
+                        TokenKind::DashGreater
+                    } else if self.peek() == '=' {
+                        self.bump();
+
+                        TokenKind::DashEqual
+                    } else {
+                        TokenKind::Dash
+                    }
+                }
+                '.' if self.peek() == '.' => {
+                    self.bump();
 
-                if self.peek() == '=' {
+                    if self.peek() == '=' {
+                        self.bump();
+
+                        TokenKind::PeriodPeriodEqual
+                    } else {
+                        TokenKind::PeriodPeriod
+                    }
+                }
+                '&' if self.peek() == '&' => {
                     self.bump();
 
-                    TokenKind::PeriodPeriodEqual
-                } else {
-                    TokenKind::PeriodPeriod
+                    TokenKind::AmpAmp
                 }
-            }
-            '0'..='9' => self.scan_integer_constant(),
-            'a'..='z' | 'A'..='Z' | '_' => self.scan_identifier(span_start),
-            ch => todo!("char not recognized: '{}'", ch),
-        };
-
-        let token_span = Span {
-            start: span_start,
-            end: self.current_peek_pos,
-        };
-
-        Some(Token {
-            kind: token_kind,
-            span: token_span,
-        })
+                '|' if self.peek() == '|' => {
+                    self.bump();
+
+                    TokenKind::PipePipe
+                }
+                '0'..='9' => self.scan_integer_constant(),
+                'a'..='z' | 'A'..='Z' | '_' => self.scan_identifier(span_start),
+                ch => {
+                    self.ctx.report(Diagnostic::error(
+                        format!("unrecognized character `{}`", ch),
+                        Span {
+                            start: span_start,
+                            end: self.current_peek_pos,
+                        },
+                    ));
+
+                    continue;
+                }
+            };
+
+            let token_span = Span {
+                start: span_start,
+                end: self.current_peek_pos,
+            };
+
+            return Some(Token {
+                kind: token_kind,
+                span: token_span,
+            });
+        }
     }
 
     fn skip_whitespace(&mut self) {
@@ -93,11 +227,55 @@ impl Scanner<'_> {
         }
     }
 
+    fn skip_line(&mut self) {
+        while !matches!(self.peek(), '\n' | Scanner::EOF_CHAR) {
+            self.bump();
+        }
+    }
+
+    /// Skips the rest of a block comment whose `/*` starts at `span_start`,
+    /// along with any block comments nested in it.
+    fn skip_block_comment(&mut self, span_start: BytePos) {
+        let mut depth = 1;
+
+        while depth > 0 {
+            match self.bump() {
+                Scanner::EOF_CHAR => {
+                    self.ctx.report(Diagnostic::error(
+                        "unterminated block comment",
+                        Span {
+                            start: span_start,
+                            end: BytePos(span_start.0 + 2),
+                        },
+                    ));
+
+                    return;
+                }
+                '/' if self.peek() == '*' => {
+                    self.bump();
+                    depth += 1;
+                }
+                '*' if self.peek() == '/' => {
+                    self.bump();
+                    depth -= 1;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Scans an integer constant along with its radix prefix, digit separators
+    /// and type suffix, if any. They are all told apart by the parser, which
+    /// reports malformed constants.
     fn scan_integer_constant(&mut self) -> TokenKind {
         while self.peek().is_ascii_digit() {
             self.bump();
         }
 
+        while matches!(self.peek(), 'a'..='z' | 'A'..='Z' | '_' | '0'..='9') {
+            self.bump();
+        }
+
         TokenKind::IntegerConstant
     }
 
@@ -109,12 +287,26 @@ impl Scanner<'_> {
         let ident_text = &self.ctx.get_source_code()[ident_span_start.0..self.current_peek_pos.0];
 
         match ident_text {
+            "i8" => TokenKind::Keyword(Keyword::I8),
+            "i16" => TokenKind::Keyword(Keyword::I16),
             "i32" => TokenKind::Keyword(Keyword::I32),
+            "i64" => TokenKind::Keyword(Keyword::I64),
+            "u8" => TokenKind::Keyword(Keyword::U8),
+            "u16" => TokenKind::Keyword(Keyword::U16),
+            "u32" => TokenKind::Keyword(Keyword::U32),
+            "u64" => TokenKind::Keyword(Keyword::U64),
+            "bool" => TokenKind::Keyword(Keyword::Bool),
+            "true" => TokenKind::Keyword(Keyword::True),
+            "false" => TokenKind::Keyword(Keyword::False),
             "if" => TokenKind::Keyword(Keyword::If),
             "else" => TokenKind::Keyword(Keyword::Else),
             "for" => TokenKind::Keyword(Keyword::For),
+            "by" => TokenKind::Keyword(Keyword::By),
             "break" => TokenKind::Keyword(Keyword::Break),
             "continue" => TokenKind::Keyword(Keyword::Continue),
+            "return" => TokenKind::Keyword(Keyword::Return),
+            "as" => TokenKind::Keyword(Keyword::As),
+            "mut" => TokenKind::Keyword(Keyword::Mut),
             _ => TokenKind::Identifier,
         }
     }
@@ -126,6 +318,13 @@ impl Scanner<'_> {
             .unwrap_or(Scanner::EOF_CHAR)
     }
 
+    fn peek_second(&self) -> char {
+        let mut chars = self.char_stream.clone();
+        chars.next();
+
+        chars.next().unwrap_or(Scanner::EOF_CHAR)
+    }
+
     fn bump(&mut self) -> char {
         let peeked = self.peek();
 
@@ -161,6 +360,17 @@ pub(crate) enum TokenKind {
     GreaterGreater,
     LessEqual,
     GreaterEqual,
+    Equal,
+    PlusEqual,
+    DashEqual,
+    StarEqual,
+    SlashEqual,
+    LessLessEqual,
+    GreaterGreaterEqual,
+    EqualEqual,
+    ExclaEqual,
+    AmpAmp,
+    PipePipe,
     Colon,
     ColonColon,
     ColonEqual,
@@ -168,6 +378,9 @@ pub(crate) enum TokenKind {
     DashGreater,
     PeriodPeriod,
     PeriodPeriodEqual,
+    /// A `///` comment, which is trivia to the parser except right before a
+    /// declaration, where it documents it.
+    DocComment,
     Keyword(Keyword),
     Open(Delim),
     Closed(Delim),
@@ -175,12 +388,26 @@ pub(crate) enum TokenKind {
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub(crate) enum Keyword {
+    I8,
+    I16,
     I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    Bool,
+    True,
+    False,
     If,
     Else,
     For,
+    By,
     Break,
     Continue,
+    Return,
+    As,
+    Mut,
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -189,11 +416,11 @@ pub(crate) enum Delim {
     Curly,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub(crate) struct Span {
     pub(crate) start: BytePos,
     pub(crate) end: BytePos,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
 pub(crate) struct BytePos(pub(crate) usize);
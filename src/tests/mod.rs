@@ -1,13 +1,78 @@
+use crate::codegen::OutputKind;
+use crate::compiler_context::CompilerContext;
 use crate::driver;
+use crate::renderer::Renderer;
 
+mod test_assignment;
 mod test_basic_programs;
 mod test_binding;
+mod test_bool;
+mod test_comments;
+mod test_const_eval;
+mod test_diagnostic_rendering;
+mod test_diagnostics;
 mod test_for_expr;
+mod test_for_range;
 mod test_function_call;
+mod test_function_params;
 mod test_if_else;
+mod test_integer_types;
+mod test_operators;
+mod test_parenthesized_expr;
+mod test_program_entry;
+mod test_return;
+mod test_scanner;
+mod test_top_level_values;
+mod test_type_check;
 
 fn compile(source_code: &str) -> String {
-    driver::compile(&strip_margin(source_code))
+    compile_to(source_code, OutputKind::Fragment)
+}
+
+/// Like `compile`, but for the given kind of output, such as a complete
+/// assembly file.
+fn compile_to(source_code: &str, output_kind: OutputKind) -> String {
+    let context = CompilerContext::new(strip_margin(source_code));
+
+    driver::compile(&context, output_kind)
+        .unwrap_or_else(|diagnostics| panic!("compilation failed: {:#?}", diagnostics))
+}
+
+/// Compiles a program, and checks the message of each diagnostic it gets, be it
+/// an error or a warning, along with the source text its primary span covers.
+fn check_diagnostics(source_code: &str, expected_diagnostics: &[(&str, &str)]) {
+    check_diagnostics_to(source_code, OutputKind::Fragment, expected_diagnostics);
+}
+
+/// Like `check_diagnostics`, but for the given kind of output.
+fn check_diagnostics_to(
+    source_code: &str,
+    output_kind: OutputKind,
+    expected_diagnostics: &[(&str, &str)],
+) {
+    use pretty_assertions::assert_eq;
+
+    let source_code = strip_margin(source_code);
+    let context = CompilerContext::new(source_code.clone());
+
+    let diagnostics = match driver::compile(&context, output_kind) {
+        Ok(_) => context.take_diagnostics(),
+        Err(diagnostics) => diagnostics,
+    };
+
+    let actual_diagnostics = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let span = diagnostic.primary_span;
+
+            (
+                diagnostic.message.as_str(),
+                &source_code[span.start.0..span.end.0],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(actual_diagnostics, expected_diagnostics);
 }
 
 fn check<S: AsRef<str>>(program: S, expected_program: &str) {
@@ -19,6 +84,24 @@ fn check<S: AsRef<str>>(program: S, expected_program: &str) {
     );
 }
 
+/// Compiles a program that must be rejected, and checks its diagnostics as they
+/// are rendered for a terminal, with colours turned off.
+fn check_rendered_diagnostics(source_code: &str, expected_output: &str) {
+    use pretty_assertions::assert_eq;
+
+    let context = CompilerContext::new(strip_margin(source_code));
+    let diagnostics =
+        driver::compile(&context, OutputKind::Fragment).expect_err("compilation should fail");
+
+    let renderer = Renderer::new(&context, "main.bit", false);
+    let rendered_output = diagnostics
+        .iter()
+        .map(|diagnostic| renderer.render(diagnostic))
+        .collect::<String>();
+
+    assert_eq!(rendered_output.trim_end(), strip_margin(expected_output));
+}
+
 fn strip_margin(text: &str) -> String {
     text.split('\n')
         .map(|line| {
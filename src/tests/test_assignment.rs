@@ -0,0 +1,207 @@
+use crate::tests::{check, check_diagnostics, check_rendered_diagnostics, compile};
+
+#[test]
+fn test_assignment_to_mutable_binding() {
+    let program = compile(
+        r#"
+        |main :: () -> i32 {
+        |    mut x := 1;
+        |    x = 5;
+        |    x
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov eax, 1
+        |    mov DWORD PTR [rbp-4], eax
+        |
+        |    mov eax, 5
+        |    mov DWORD PTR [rbp-4], eax
+        |
+        |    mov eax, DWORD PTR [rbp-4]
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_compound_assignments() {
+    let program = compile(
+        r#"
+        |main :: (n: u8) -> u8 {
+        |    mut x := n;
+        |    x -= 1;
+        |    x /= 2;
+        |    x <<= 3;
+        |    x
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov BYTE PTR [rbp-1], dil
+        |
+        |    mov al, BYTE PTR [rbp-1]
+        |    mov BYTE PTR [rbp-2], al
+        |
+        |    mov al, 1
+        |    mov cl, al
+        |    mov al, BYTE PTR [rbp-2]
+        |    sub al, cl
+        |    mov BYTE PTR [rbp-2], al
+        |
+        |    mov al, 2
+        |    mov cl, al
+        |    mov al, BYTE PTR [rbp-2]
+        |    movzx eax, al
+        |    movzx ecx, cl
+        |    xor edx, edx
+        |    div ecx
+        |    mov BYTE PTR [rbp-2], al
+        |
+        |    mov al, 3
+        |    mov cl, al
+        |    mov al, BYTE PTR [rbp-2]
+        |    shl al, cl
+        |    mov BYTE PTR [rbp-2], al
+        |
+        |    mov al, BYTE PTR [rbp-2]
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_assignment_inside_loop() {
+    let program = compile(
+        r#"
+        |main :: () -> i32 {
+        |    mut sum := 0;
+        |    for i : 1..=10 {
+        |        sum += i;
+        |    }
+        |    sum
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov eax, 0
+        |    mov DWORD PTR [rbp-4], eax
+        |
+        |    mov eax, 1
+        |    mov DWORD PTR [rbp-8], eax
+        |.L0:
+        |    mov eax, DWORD PTR [rbp-8]
+        |    cmp eax, 10
+        |    jg .L1
+        |
+        |    mov eax, DWORD PTR [rbp-8]
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    add eax, ecx
+        |    mov DWORD PTR [rbp-4], eax
+        |
+        |    mov eax, DWORD PTR [rbp-8]
+        |    add eax, 1
+        |    jo .L1
+        |    mov DWORD PTR [rbp-8], eax
+        |    jmp .L0
+        |.L1:
+        |    mov eax, DWORD PTR [rbp-4]
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_assignment_to_immutable_binding() {
+    check_rendered_diagnostics(
+        r#"
+        |main :: () {
+        |    x := 1;
+        |    x += 2;
+        |}
+        |"#,
+        r#"
+        |error: cannot assign to `x`, as it is not mutable
+        | --> main.bit:3:5
+        |  |
+        |2 |     x := 1;
+        |  |     - `x` is defined here
+        |3 |     x += 2;
+        |  |     ^^^^^^
+        |  = note: only bindings defined with `mut` can be assigned to"#,
+    );
+}
+
+#[test]
+fn test_only_mut_bindings_can_be_assigned_to() {
+    check_diagnostics(
+        r#"
+        |main :: (a: i32) {
+        |    a = 2;
+        |    for i : 0..3 {
+        |        i += 1;
+        |    }
+        |    main = 4;
+        |    y = 5;
+        |}
+        |"#,
+        &[
+            ("cannot assign to `a`, as it is not mutable", "a = 2"),
+            ("cannot assign to `i`, as it is not mutable", "i += 1"),
+            ("cannot assign to `main`, as it is not mutable", "main = 4"),
+            ("cannot find binding `y` in this scope", "y"),
+        ],
+    );
+}
+
+#[test]
+fn test_assigned_value_of_wrong_type() {
+    check_diagnostics(
+        r#"
+        |main :: () {
+        |    mut b := true;
+        |    b = 1;
+        |    b += 1;
+        |    mut x := 1u8;
+        |    x = 256;
+        |    x = b;
+        |}
+        |"#,
+        &[
+            ("mismatched types: expected `bool`, found `i32`", "1"),
+            ("cannot apply operator `+=` to type `bool`", "b += 1"),
+            ("integer constant is out of range for `u8`", "256"),
+            ("mismatched types: expected `u8`, found `bool`", "b"),
+        ],
+    );
+}
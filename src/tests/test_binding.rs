@@ -17,14 +17,14 @@ fn test_bind_to_primary_expr_and_return_from_function() {
         |main:
         |    push rbp
         |    mov rbp, rsp
-        |    sub rsp, 4
+        |    sub rsp, 16
         |
         |    mov eax, 42
         |    mov DWORD PTR [rbp-4], eax
         |
         |    mov eax, DWORD PTR [rbp-4]
         |
-        |    add rsp, 4
+        |    add rsp, 16
         |    pop rbp
         |    ret
         |"#,
@@ -102,20 +102,20 @@ fn test_allocate_stack_for_many_functions() {
         |func1:
         |    push rbp
         |    mov rbp, rsp
-        |    sub rsp, 8
+        |    sub rsp, 16
         |    mov eax, 42
         |    mov DWORD PTR [rbp-4], eax
         |    mov eax, 314
         |    mov DWORD PTR [rbp-8], eax
         |    mov eax, DWORD PTR [rbp-4]
-        |    add rsp, 8
+        |    add rsp, 16
         |    pop rbp
         |    ret
         |
         |func2:
         |    push rbp
         |    mov rbp, rsp
-        |    sub rsp, 12
+        |    sub rsp, 16
         |    mov eax, 1
         |    mov DWORD PTR [rbp-4], eax
         |    mov eax, 2
@@ -123,7 +123,7 @@ fn test_allocate_stack_for_many_functions() {
         |    mov eax, 3
         |    mov DWORD PTR [rbp-12], eax
         |    mov eax, DWORD PTR [rbp-4]
-        |    add rsp, 12
+        |    add rsp, 16
         |    pop rbp
         |    ret
         |"#,
@@ -193,7 +193,7 @@ fn test_access_outer_scope_bindings() {
         |    foo := 1;
         |
         |    {
-        |        foo;
+        |        foo
         |    }
         |}
         |"#,
@@ -205,14 +205,14 @@ fn test_access_outer_scope_bindings() {
         |func:
         |    push rbp
         |    mov rbp, rsp
-        |    sub rsp, 4
+        |    sub rsp, 16
         |
         |    mov eax, 1
         |    mov DWORD PTR [rbp-4], eax
         |
         |    mov eax, DWORD PTR [rbp-4]
         |
-        |    add rsp, 4
+        |    add rsp, 16
         |    pop rbp
         |    ret
         |"#,
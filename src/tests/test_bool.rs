@@ -0,0 +1,214 @@
+use crate::tests::{check, check_diagnostics, compile};
+
+#[test]
+fn test_bool_constants_and_equality() {
+    let program = compile(
+        r#"
+        |main :: () -> bool {
+        |    x := true;
+        |    y := false;
+        |    x == y
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov eax, 1
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov eax, 0
+        |    mov DWORD PTR [rbp-8], eax
+        |
+        |    mov eax, DWORD PTR [rbp-4]
+        |    mov DWORD PTR [rbp-12], eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-12]
+        |    cmp eax, ecx
+        |    sete al
+        |    movzx eax, al
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_comparisons_in_conditions_branch_on_flags() {
+    let program = compile(
+        r#"
+        |main :: (a: i32, b: i32) -> i32 {
+        |    if a < b && b < 10 {
+        |        1
+        |    } else {
+        |        0
+        |    }
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov DWORD PTR [rbp-8], esi
+        |
+        |    mov eax, DWORD PTR [rbp-4]
+        |    mov DWORD PTR [rbp-12], eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-12]
+        |    cmp eax, ecx
+        |    jge .L0                         ; a < b is false, skip the rest
+        |
+        |    mov eax, DWORD PTR [rbp-8]
+        |    mov DWORD PTR [rbp-16], eax
+        |    mov eax, 10
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-16]
+        |    cmp eax, ecx
+        |    jge .L0
+        |
+        |    mov eax, 1
+        |    jmp .L1
+        |.L0:
+        |    mov eax, 0
+        |.L1:
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_logical_or_short_circuits() {
+    let program = compile(
+        r#"
+        |main :: (a: bool, b: bool) -> i32 {
+        |    if a || !b {
+        |        1
+        |    } else {
+        |        0
+        |    }
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov DWORD PTR [rbp-8], esi
+        |
+        |    mov eax, DWORD PTR [rbp-4]
+        |    cmp eax, 0
+        |    jne .L1                         ; a is true, skip b
+        |
+        |    mov eax, DWORD PTR [rbp-8]
+        |    cmp eax, 0
+        |    jne .L0                         ; !b is false
+        |
+        |.L1:
+        |    mov eax, 1
+        |    jmp .L2
+        |.L0:
+        |    mov eax, 0
+        |.L2:
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_logical_and_as_a_value() {
+    let program = compile(
+        r#"
+        |both :: (a: bool, b: bool) -> bool {
+        |    a && b
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |both:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov DWORD PTR [rbp-8], esi
+        |
+        |    mov eax, DWORD PTR [rbp-4]
+        |    cmp eax, 0
+        |    je .L0
+        |    mov eax, DWORD PTR [rbp-8]
+        |    cmp eax, 0
+        |    je .L0
+        |
+        |    mov eax, 1
+        |    jmp .L1
+        |.L0:
+        |    mov eax, 0
+        |.L1:
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_integer_used_as_a_condition() {
+    check_diagnostics(
+        r#"
+        |main :: (x: i32) {
+        |    if x {}
+        |    for x - 1 {}
+        |}
+        |"#,
+        &[
+            ("mismatched types: expected `bool`, found `i32`", "x"),
+            ("mismatched types: expected `bool`, found `i32`", "x - 1"),
+        ],
+    );
+}
+
+#[test]
+fn test_logical_operators_take_bools() {
+    check_diagnostics(
+        r#"
+        |main :: (x: i32, b: bool) -> bool {
+        |    !x;
+        |    b && 1;
+        |    b + 1;
+        |    b == x
+        |}
+        |"#,
+        &[
+            ("mismatched types: expected `bool`, found `i32`", "x"),
+            ("mismatched types: expected `bool`, found `i32`", "1"),
+            ("mismatched types: expected an integer, found `bool`", "b"),
+            ("mismatched types: expected `bool`, found `i32`", "x"),
+        ],
+    );
+}
@@ -0,0 +1,117 @@
+use crate::compiler_context::CompilerContext;
+use crate::parser::Parser;
+use crate::scanner::Scanner;
+use crate::tests::{check, check_diagnostics, compile, strip_margin};
+
+#[test]
+fn test_comments_are_skipped() {
+    let program = compile(
+        r#"
+        |// The answer.
+        |main :: () -> i32 { // Takes no arguments.
+        |    ////////////////
+        |    x := 4 /* a block comment */ * 10;
+        |    /*
+        |     * This one spans lines, /* and has /* nested */ comments */ inside.
+        |     */
+        |    x + 2 // The end.
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov eax, 4
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov eax, 10
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    imul eax, ecx
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    mov DWORD PTR [rbp-12], eax
+        |    mov eax, 2
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-12]
+        |    add eax, ecx
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_unterminated_block_comment() {
+    check_diagnostics(
+        r#"
+        |main :: () {}
+        |
+        |/* Nested /* comments */ need to be closed too.
+        |"#,
+        &[("unterminated block comment", "/*")],
+    );
+}
+
+#[test]
+fn test_doc_comments_are_attached_to_declarations() {
+    let source_code = strip_margin(
+        r#"
+        |/// Adds two numbers.
+        |///
+        |/// Overflows wrap around.
+        |add :: (a: i32, b: i32) -> i32 {
+        |    a + b
+        |}
+        |
+        |/// Still documents `main`, as plain comments are skipped.
+        |// Just a comment.
+        |main :: () {}
+        |"#,
+    );
+    let context = CompilerContext::new(source_code);
+    let tokens = Scanner::new(&context).scan_all_tokens();
+    let program = Parser::new(tokens, &context).parse_program();
+
+    let doc_comments = program
+        .decls
+        .iter()
+        .map(|decl| {
+            decl.doc_comments
+                .iter()
+                .map(|span| &context.get_source_code()[span.start.0..span.end.0])
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        doc_comments,
+        [
+            vec!["/// Adds two numbers.", "///", "/// Overflows wrap around."],
+            vec!["/// Still documents `main`, as plain comments are skipped."],
+        ]
+    );
+}
+
+#[test]
+fn test_doc_comments_that_document_nothing() {
+    check_diagnostics(
+        r#"
+        |main :: () -> i32 {
+        |    /// The answer.
+        |    42
+        |}
+        |
+        |/// The end.
+        |"#,
+        &[
+            ("unused doc comment", "/// The answer."),
+            ("unused doc comment", "/// The end."),
+        ],
+    );
+}
@@ -0,0 +1,264 @@
+use crate::tests::{check, check_diagnostics, check_rendered_diagnostics, compile};
+
+#[test]
+fn test_constants_computed_by_functions() {
+    let program = compile(
+        r#"
+        |fact10 :: factorial(10)
+        |size :: if area > 20 { area / 2 } else { area }
+        |area :: square(3) + square(4)
+        |
+        |square :: (n: i32) -> i32 {
+        |    n * n
+        |}
+        |
+        |factorial :: (n: i64) -> i64 {
+        |    mut result := 1i64;
+        |    for i : 2..=n {
+        |        result *= i;
+        |    }
+        |    result
+        |}
+        |
+        |main :: () -> i64 {
+        |    fact10 + size as i64
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |square:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov eax, DWORD PTR [rbp-4]
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    imul eax, ecx
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |factorial:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 32
+        |    mov QWORD PTR [rbp-8], rdi
+        |    mov rax, 1
+        |    mov QWORD PTR [rbp-16], rax
+        |    mov rax, 2
+        |    mov QWORD PTR [rbp-24], rax
+        |    mov rax, QWORD PTR [rbp-8]
+        |    mov QWORD PTR [rbp-32], rax
+        |.L0:
+        |    mov rax, QWORD PTR [rbp-24]
+        |    cmp rax, QWORD PTR [rbp-32]
+        |    jg .L1
+        |    mov rax, QWORD PTR [rbp-24]
+        |    mov rcx, rax
+        |    mov rax, QWORD PTR [rbp-16]
+        |    imul rax, rcx
+        |    mov QWORD PTR [rbp-16], rax
+        |    mov rax, QWORD PTR [rbp-24]
+        |    add rax, 1
+        |    jo .L1
+        |    mov QWORD PTR [rbp-24], rax
+        |    jmp .L0
+        |.L1:
+        |    mov rax, QWORD PTR [rbp-16]
+        |    add rsp, 32
+        |    pop rbp
+        |    ret
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov rax, 3628800
+        |    mov QWORD PTR [rbp-8], rax
+        |    mov eax, 12
+        |    movsxd rax, eax
+        |    mov rcx, rax
+        |    mov rax, QWORD PTR [rbp-8]
+        |    add rax, rcx
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_range_with_bounds_known_at_compile_time() {
+    let program = compile(
+        r#"
+        |rows :: 4
+        |columns :: rows * 2
+        |
+        |count :: () -> i32 {
+        |    mut total := 0;
+        |    for i : 0..rows * columns by columns / 2 {
+        |        total += 1;
+        |    }
+        |    total
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |count:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov eax, 0
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov eax, 0
+        |    mov DWORD PTR [rbp-8], eax
+        |.L0:
+        |    mov eax, DWORD PTR [rbp-8]
+        |    cmp eax, 32
+        |    jge .L1
+        |    mov eax, 1
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    add eax, ecx
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    add eax, 4
+        |    jo .L1
+        |    mov DWORD PTR [rbp-8], eax
+        |    jmp .L0
+        |.L1:
+        |    mov eax, DWORD PTR [rbp-4]
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_arithmetic_errors_in_constants() {
+    check_diagnostics(
+        r#"
+        |big :: 2147483647 + 1
+        |half :: 10 / (big - big)
+        |zero :: 0
+        |ratio :: 7 / zero
+        |wide :: 1u8 << 8u8
+        |low :: -(-128i8)
+        |"#,
+        &[
+            (
+                "attempt to compute `2147483647 + 1`, which overflows `i32`",
+                "2147483647 + 1",
+            ),
+            ("attempt to divide `7` by zero", "7 / zero"),
+            (
+                "attempt to compute `1 << 8`, which overflows `u8`",
+                "1u8 << 8u8",
+            ),
+            (
+                "attempt to negate `-128`, which overflows `i8`",
+                "-(-128i8)",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_constants_that_take_too_long_to_evaluate() {
+    check_diagnostics(
+        r#"
+        |forever :: spin()
+        |deep :: depth(0)
+        |
+        |spin :: () -> i32 {
+        |    for {}
+        |}
+        |
+        |depth :: (n: i32) -> i32 {
+        |    depth(n + 1)
+        |}
+        |"#,
+        &[
+            (
+                "the value of `forever` takes too long to evaluate",
+                "spin()",
+            ),
+            ("the value of `deep` takes too long to evaluate", "depth(0)"),
+        ],
+    );
+}
+
+#[test]
+fn test_constants_that_depend_on_themselves() {
+    check_diagnostics(
+        r#"
+        |a :: b + 1
+        |b :: a
+        |"#,
+        &[("the value of `a` depends on itself", "a")],
+    );
+
+    check_diagnostics(
+        r#"
+        |loopy :: twice()
+        |
+        |twice :: () -> i32 {
+        |    loopy * 2
+        |}
+        |"#,
+        &[("the value of `loopy` depends on itself", "loopy")],
+    );
+}
+
+#[test]
+fn test_errors_deep_in_functions_that_constants_call() {
+    check_rendered_diagnostics(
+        r#"
+        |mut counter := 0
+        |bumped :: bump()
+        |bump :: () -> i32 {
+        |    counter += 1;
+        |    counter
+        |}
+        |"#,
+        r#"
+        |error: the value of `bumped` must be known at compile time
+        | --> main.bit:2:11
+        |  |
+        |2 | bumped :: bump()
+        |  |           ^^^^^^
+        |...
+        |4 |     counter += 1;
+        |  |     ------------ this is only known at run time"#,
+    );
+
+    check_rendered_diagnostics(
+        r#"
+        |F10 :: f(10)
+        |F20 :: f(20)
+        |f :: (n: i32) -> i32 {
+        |    if n <= 1 {
+        |        return 1;
+        |    }
+        |    n * f(n - 1)
+        |}
+        |"#,
+        r#"
+        |error: attempt to compute `13 * 479001600`, which overflows `i32`
+        | --> main.bit:7:5
+        |  |
+        |2 | F20 :: f(20)
+        |  |        ----- while evaluating `F20` here
+        |...
+        |7 |     n * f(n - 1)
+        |  |     ^^^^^^^^^^^^"#,
+    );
+}
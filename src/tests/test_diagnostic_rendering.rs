@@ -0,0 +1,100 @@
+use crate::tests::check_rendered_diagnostics;
+
+#[test]
+fn test_render_error_with_source_line() {
+    check_rendered_diagnostics(
+        r#"
+        |main :: () {
+        |    x := y + 1
+        |}
+        |"#,
+        r#"
+        |error: cannot find binding `y` in this scope
+        | --> main.bit:2:10
+        |  |
+        |2 |     x := y + 1
+        |  |          ^
+        |"#,
+    );
+}
+
+#[test]
+fn test_render_secondary_label_on_another_line() {
+    check_rendered_diagnostics(
+        r#"
+        |foo :: (a: i32) {}
+        |
+        |main :: () {
+        |    foo(1, 2)
+        |}
+        |"#,
+        r#"
+        |error: function `foo` takes 1 argument(s) but 2 were supplied
+        | --> main.bit:3:5
+        |  |
+        |1 | foo :: (a: i32) {}
+        |  | --- function defined here
+        |...
+        |3 |     foo(1, 2)
+        |  |     ^^^^^^^^^
+        |"#,
+    );
+}
+
+#[test]
+fn test_render_notes() {
+    check_rendered_diagnostics(
+        r#"
+        |main :: (x) -> i32 {
+        |    x
+        |}
+        |"#,
+        r#"
+        |error: expected a function literal, found `->`
+        | --> main.bit:1:13
+        |  |
+        |1 | main :: (x) -> i32 {
+        |  |         --- this is parsed as a parenthesized expression
+        |  |             ^^
+        |  = note: function parameters are written as `name: type`
+        |"#,
+    );
+}
+
+#[test]
+fn test_render_help() {
+    check_rendered_diagnostics(
+        r#"
+        |main :: () -> i32 {
+        |    010
+        |}
+        |"#,
+        r#"
+        |error: leading zeros are not allowed in a decimal constant
+        | --> main.bit:2:5
+        |  |
+        |2 |     010
+        |  |     ^^^
+        |  = help: use the `0o` prefix for an octal constant
+        |"#,
+    );
+}
+
+#[test]
+fn test_render_unclosed_delimiter_at_end_of_file() {
+    check_rendered_diagnostics(
+        r#"
+        |main :: () {
+        |    x := 1
+        |"#,
+        r#"
+        |error: expected `}` to close the block, found end of file
+        | --> main.bit:2:11
+        |  |
+        |1 | main :: () {
+        |  |            - unclosed delimiter
+        |2 |     x := 1
+        |  |           ^
+        |"#,
+    );
+}
@@ -0,0 +1,227 @@
+use crate::tests::check_diagnostics;
+
+#[test]
+fn test_unrecognized_character() {
+    check_diagnostics(
+        r#"
+        |main :: () {
+        |    x := 1 $ 2
+        |}
+        |"#,
+        &[("unrecognized character `$`", "$")],
+    );
+}
+
+#[test]
+fn test_missing_declaration_operator() {
+    check_diagnostics(
+        r#"
+        |main () {}
+        |"#,
+        &[("expected `::` after the declaration name, found `()`", "()")],
+    );
+}
+
+#[test]
+fn test_unexpected_end_of_file() {
+    check_diagnostics(
+        r#"
+        |main :: () {
+        |    x := 1 +
+        |"#,
+        &[("expected an expression, found end of file", "")],
+    );
+}
+
+#[test]
+fn test_undefined_binding() {
+    check_diagnostics(
+        r#"
+        |main :: () {
+        |    x := y + 1
+        |}
+        |"#,
+        &[("cannot find binding `y` in this scope", "y")],
+    );
+}
+
+#[test]
+fn test_break_outside_of_loop() {
+    check_diagnostics(
+        r#"
+        |main :: () {
+        |    break
+        |}
+        |"#,
+        &[("`break` outside of a loop", "break")],
+    );
+}
+
+#[test]
+fn test_call_to_top_level_declaration_that_is_not_a_function() {
+    check_diagnostics(
+        r#"
+        |answer :: 42
+        |
+        |main :: () {
+        |    answer()
+        |}
+        |"#,
+        &[("`answer` is not a function", "answer")],
+    );
+}
+
+#[test]
+fn test_syntax_errors_in_several_declarations() {
+    check_diagnostics(
+        r#"
+        |foo :: () -> i32 {
+        |    1 + * 2
+        |}
+        |
+        |bar :: () -> i32 {
+        |    (3
+        |}
+        |
+        |main :: () {
+        |    foo()
+        |}
+        |"#,
+        &[
+            ("expected an expression, found `*`", "*"),
+            (
+                "expected `)` to close the parenthesized expression, found `}`",
+                "}",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_missing_closing_curly_before_next_declaration() {
+    check_diagnostics(
+        r#"
+        |foo :: (a: i32) {
+        |    x := a
+        |
+        |main :: () {
+        |    foo(1, 2)
+        |}
+        |"#,
+        &[("expected `}` to close the block, found `main`", "main")],
+    );
+}
+
+#[test]
+fn test_block_after_syntax_error_is_still_checked() {
+    check_diagnostics(
+        r#"
+        |main :: () {
+        |    {
+        |        1 +
+        |    }
+        |    y
+        |}
+        |"#,
+        &[
+            ("expected an expression, found `}`", "}"),
+            ("cannot find binding `y` in this scope", "y"),
+        ],
+    );
+}
+
+#[test]
+fn test_stray_token_between_declarations() {
+    check_diagnostics(
+        r#"
+        |foo :: () {}
+        |}
+        |main :: () {}
+        |"#,
+        &[("expected a declaration name, found `}`", "}")],
+    );
+}
+
+#[test]
+fn test_undefined_function() {
+    check_diagnostics(
+        r#"
+        |main :: () {
+        |    foo(1)
+        |}
+        |"#,
+        &[("cannot find function `foo` in this scope", "foo")],
+    );
+}
+
+#[test]
+fn test_duplicate_top_level_declaration() {
+    check_diagnostics(
+        r#"
+        |foo :: () {}
+        |
+        |foo :: (a: i32) {}
+        |
+        |main :: () {}
+        |"#,
+        &[("the name `foo` is defined multiple times", "foo")],
+    );
+}
+
+#[test]
+fn test_duplicate_parameter() {
+    check_diagnostics(
+        r#"
+        |add :: (a: i32, a: i32) -> i32 {
+        |    a
+        |}
+        |"#,
+        &[("the name `a` is defined multiple times", "a")],
+    );
+}
+
+#[test]
+fn test_redefine_binding_in_the_same_block() {
+    check_diagnostics(
+        r#"
+        |main :: () {
+        |    x := 1;
+        |    {
+        |        x := 2;
+        |    }
+        |    x := 3;
+        |}
+        |"#,
+        &[("the name `x` is defined multiple times", "x")],
+    );
+}
+
+#[test]
+fn test_binding_shadows_top_level_declaration() {
+    check_diagnostics(
+        r#"
+        |foo :: () {}
+        |
+        |main :: () {
+        |    foo := 1;
+        |}
+        |"#,
+        &[(
+            "`foo` shadows the top-level declaration of the same name",
+            "foo",
+        )],
+    );
+}
+
+#[test]
+fn test_function_literal_does_not_see_outer_bindings() {
+    check_diagnostics(
+        r#"
+        |main :: () {
+        |    x := 1;
+        |    f := (y: i32) -> i32 { x }
+        |}
+        |"#,
+        &[("cannot find binding `x` in this scope", "x")],
+    );
+}
@@ -1,4 +1,4 @@
-use crate::tests::{check, compile};
+use crate::tests::{check, check_diagnostics, compile};
 
 #[test]
 fn test_empty_infinite_for_loop() {
@@ -47,7 +47,7 @@ fn test_infinite_for_loop() {
         |main:
         |    push rbp
         |    mov rbp, rsp
-        |    sub rsp, 4
+        |    sub rsp, 16
         |
         |.L0:
         |    mov eax, 42
@@ -56,7 +56,7 @@ fn test_infinite_for_loop() {
         |    jmp .L0
         |
         |.L1:
-        |    add rsp, 4
+        |    add rsp, 16
         |    pop rbp
         |    ret
         |
@@ -74,7 +74,7 @@ fn test_conditional_empty_infinite_for_loop() {
     let program = compile(
         r#"
         |main :: () {
-        |    for 1 {
+        |    for true {
         |    }
         |}
         |"#,
@@ -87,9 +87,6 @@ fn test_conditional_empty_infinite_for_loop() {
         |    push rbp
         |    mov rbp, rsp
         |.L0:
-        |    mov eax, 1
-        |    cmp eax, 0
-        |    je .L1
         |    jmp .L0
         |.L1:
         |    pop rbp
@@ -103,11 +100,13 @@ fn test_conditional_for_loop() {
     let program = compile(
         r#"
         |main :: () {
-        |    for 1 {
+        |    for running() {
         |        foo();
         |    }
         |}
         |
+        |running :: () -> bool { true }
+        |
         |foo :: () {}
         |"#,
     );
@@ -120,7 +119,7 @@ fn test_conditional_for_loop() {
         |    mov rbp, rsp
         |
         |.L0:
-        |    mov eax, 1
+        |    call running
         |    cmp eax, 0
         |    je .L1
         |
@@ -132,6 +131,13 @@ fn test_conditional_for_loop() {
         |    pop rbp
         |    ret
         |
+        |running:
+        |    push rbp
+        |    mov rbp, rsp
+        |    mov eax, 1
+        |    pop rbp
+        |    ret
+        |
         |foo:
         |    push rbp
         |    mov rbp, rsp
@@ -159,7 +165,7 @@ fn test_iteration_for_loop_exclusive_range() {
         |main:
         |    push rbp
         |    mov rbp, rsp
-        |    sub rsp, 4
+        |    sub rsp, 16
         |
         |    mov eax, 3
         |    mov DWORD PTR [rbp-4], eax
@@ -173,11 +179,12 @@ fn test_iteration_for_loop_exclusive_range() {
         |
         |    mov eax, DWORD PTR [rbp-4]
         |    add eax, 1
+        |    jo .L1
         |    mov DWORD PTR [rbp-4], eax
         |    jmp .L0
         |
         |.L1:
-        |    add rsp, 4
+        |    add rsp, 16
         |    pop rbp
         |    ret
         |"#,
@@ -202,7 +209,7 @@ fn test_iteration_for_loop_inclusive_range() {
         |main:
         |    push rbp
         |    mov rbp, rsp
-        |    sub rsp, 4
+        |    sub rsp, 16
         |
         |    mov eax, 3
         |    mov DWORD PTR [rbp-4], eax
@@ -216,18 +223,18 @@ fn test_iteration_for_loop_inclusive_range() {
         |
         |    mov eax, DWORD PTR [rbp-4]
         |    add eax, 1
+        |    jo .L1
         |    mov DWORD PTR [rbp-4], eax
         |    jmp .L0
         |
         |.L1:
-        |    add rsp, 4
+        |    add rsp, 16
         |    pop rbp
         |    ret
         |"#,
     );
 }
 
-
 #[test]
 fn test_break_infinite_for_loop() {
     let program = compile(
@@ -346,7 +353,7 @@ fn test_breaks_iterative_for_loop() {
         |main:
         |    push rbp
         |    mov rbp, rsp
-        |    sub rsp, 4
+        |    sub rsp, 16
         |    mov eax, 0
         |    mov DWORD PTR [rbp-4], eax
         |.L0:
@@ -358,10 +365,11 @@ fn test_breaks_iterative_for_loop() {
         |
         |    mov eax, DWORD PTR [rbp-4]
         |    add eax, 1
+        |    jo .L1
         |    mov DWORD PTR [rbp-4], eax
         |    jmp .L0
         |.L1:
-        |    add rsp, 4
+        |    add rsp, 16
         |    pop rbp
         |    ret
         |"#,
@@ -395,3 +403,302 @@ fn test_continue_infinite_for_loop() {
         |"#,
     );
 }
+
+#[test]
+fn test_break_with_value() {
+    let program = compile(
+        r#"
+        |main :: (n: i64) -> i64 {
+        |    mut i := 0i64;
+        |    for {
+        |        if i == n {
+        |            break i * 2;
+        |        }
+        |        i += 1;
+        |    }
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 32
+        |    mov QWORD PTR [rbp-8], rdi
+        |    mov rax, 0
+        |    mov QWORD PTR [rbp-16], rax
+        |.L0:
+        |    mov rax, QWORD PTR [rbp-16]
+        |    mov QWORD PTR [rbp-24], rax
+        |    mov rax, QWORD PTR [rbp-8]
+        |    mov rcx, rax
+        |    mov rax, QWORD PTR [rbp-24]
+        |    cmp rax, rcx
+        |    jne .L2
+        |
+        |    mov rax, QWORD PTR [rbp-16]
+        |    mov QWORD PTR [rbp-32], rax
+        |    mov rax, 2
+        |    mov rcx, rax
+        |    mov rax, QWORD PTR [rbp-32]
+        |    imul rax, rcx
+        |    jmp .L1                         ; the loop's value is left in `rax`
+        |.L2:
+        |    mov rax, 1
+        |    mov rcx, rax
+        |    mov rax, QWORD PTR [rbp-16]
+        |    add rax, rcx
+        |    mov QWORD PTR [rbp-16], rax
+        |    jmp .L0
+        |.L1:
+        |    add rsp, 32
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_breaks_of_a_loop_must_agree_on_its_type() {
+    check_diagnostics(
+        r#"
+        |main :: (a: bool) {
+        |    x := for {
+        |        if a {
+        |            break 1;
+        |        }
+        |        break;
+        |    };
+        |    y := for {
+        |        for {
+        |            break true;
+        |        };
+        |        break 2;
+        |    };
+        |}
+        |"#,
+        &[("mismatched types: expected `i32`, found `()`", "break")],
+    );
+}
+
+#[test]
+fn test_break_with_value_in_loop_that_ends_on_its_own() {
+    check_diagnostics(
+        r#"
+        |main :: (a: bool) {
+        |    for a {
+        |        break 1;
+        |    }
+        |    for i : 0..10 {
+        |        break i;
+        |    }
+        |}
+        |"#,
+        &[
+            (
+                "`break` with a value in a `for` that can end on its own",
+                "break 1",
+            ),
+            (
+                "`break` with a value in a `for` that can end on its own",
+                "break i",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_break_and_continue_outer_loop() {
+    let program = compile(
+        r#"
+        |main :: () -> i32 {
+        |    mut n := 0;
+        |    outer: for i : 0..10 {
+        |        for {
+        |            if n > 20 {
+        |                break outer
+        |            }
+        |            n += i;
+        |            continue outer
+        |        }
+        |    }
+        |    n
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov eax, 0
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov eax, 0
+        |    mov DWORD PTR [rbp-8], eax
+        |.L0:               ; start of outer for-loop
+        |    mov eax, DWORD PTR [rbp-8]
+        |    cmp eax, 10
+        |    jge .L1
+        |.L2:               ; start of inner for-loop
+        |    mov eax, DWORD PTR [rbp-4]
+        |    mov DWORD PTR [rbp-12], eax
+        |    mov eax, 20
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-12]
+        |    cmp eax, ecx
+        |    jle .L4
+        |    jmp .L1        ; break outer
+        |.L4:
+        |    mov eax, DWORD PTR [rbp-8]
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    add eax, ecx
+        |    mov DWORD PTR [rbp-4], eax
+        |    jmp .L5        ; continue outer
+        |    jmp .L2
+        |.L3:               ; exit of inner for-loop
+        |.L5:               ; step of outer for-loop
+        |    mov eax, DWORD PTR [rbp-8]
+        |    add eax, 1
+        |    jo .L1
+        |    mov DWORD PTR [rbp-8], eax
+        |    jmp .L0
+        |.L1:               ; exit of outer for-loop
+        |    mov eax, DWORD PTR [rbp-4]
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_break_outer_loop_with_value() {
+    let program = compile(
+        r#"
+        |main :: () -> i64 {
+        |    search: for {
+        |        for i : 0i64..100 {
+        |            if i * i > 50 {
+        |                break search i
+        |            }
+        |        }
+        |    }
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 32
+        |.L0:
+        |    mov rax, 0
+        |    mov QWORD PTR [rbp-8], rax
+        |.L2:
+        |    mov rax, QWORD PTR [rbp-8]
+        |    cmp rax, 100
+        |    jge .L3
+        |    mov rax, QWORD PTR [rbp-8]
+        |    mov QWORD PTR [rbp-16], rax
+        |    mov rax, QWORD PTR [rbp-8]
+        |    mov rcx, rax
+        |    mov rax, QWORD PTR [rbp-16]
+        |    imul rax, rcx
+        |    mov QWORD PTR [rbp-24], rax
+        |    mov rax, 50
+        |    mov rcx, rax
+        |    mov rax, QWORD PTR [rbp-24]
+        |    cmp rax, rcx
+        |    jle .L4
+        |    mov rax, QWORD PTR [rbp-8]
+        |    jmp .L1
+        |.L4:
+        |    mov rax, QWORD PTR [rbp-8]
+        |    add rax, 1
+        |    jo .L3
+        |    mov QWORD PTR [rbp-8], rax
+        |    jmp .L2
+        |.L3:
+        |    jmp .L0
+        |.L1:
+        |    add rsp, 32
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_labels_that_refer_to_no_loop() {
+    check_diagnostics(
+        r#"
+        |main :: (a: bool) {
+        |    outer: for a {
+        |        outer: for {
+        |            break outer 1
+        |        }
+        |    }
+        |    for {
+        |        continue inner
+        |    }
+        |    continue;
+        |}
+        |"#,
+        &[
+            (
+                "label `outer` shadows the label of an enclosing loop",
+                "outer",
+            ),
+            ("cannot find label `inner` in this scope", "inner"),
+            ("`continue` outside of a loop", "continue"),
+        ],
+    );
+}
+
+#[test]
+fn test_break_to_a_label_that_is_not_defined() {
+    check_diagnostics(
+        r#"
+        |main :: (n: i32) -> i32 {
+        |    outer: for {
+        |        for {
+        |            break inner
+        |        }
+        |        break n
+        |    }
+        |}
+        |"#,
+        &[("cannot find label `inner` in this scope", "inner")],
+    );
+}
+
+#[test]
+fn test_labels_do_not_reach_into_function_literals() {
+    check_diagnostics(
+        r#"
+        |main :: () {
+        |    outer: for {
+        |        f := () -> i32 {
+        |            outer := true;
+        |            for { break outer }
+        |        };
+        |        break outer
+        |    }
+        |}
+        |"#,
+        &[(
+            "mismatched types: expected `i32`, found `bool`",
+            "for { break outer }",
+        )],
+    );
+}
@@ -0,0 +1,337 @@
+use crate::tests::{check, check_diagnostics, compile};
+
+#[test]
+fn test_range_bounds_and_step_are_evaluated_once() {
+    let program = compile(
+        r#"
+        |sum :: (n: i32, k: i32) -> i32 {
+        |    mut total := 0;
+        |    for i : 0..n * 2 by k {
+        |        total += i;
+        |    }
+        |    total
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |sum:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 32
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov DWORD PTR [rbp-8], esi
+        |    mov eax, 0
+        |    mov DWORD PTR [rbp-12], eax
+        |    mov eax, 0
+        |    mov DWORD PTR [rbp-16], eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    mov DWORD PTR [rbp-20], eax
+        |    mov eax, 2
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-20]
+        |    imul eax, ecx
+        |    mov DWORD PTR [rbp-24], eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    mov DWORD PTR [rbp-28], eax
+        |    cmp DWORD PTR [rbp-28], 0
+        |    jle .L1
+        |.L0:
+        |    mov eax, DWORD PTR [rbp-16]
+        |    cmp eax, DWORD PTR [rbp-24]
+        |    jge .L1
+        |    mov eax, DWORD PTR [rbp-16]
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-12]
+        |    add eax, ecx
+        |    mov DWORD PTR [rbp-12], eax
+        |    mov eax, DWORD PTR [rbp-16]
+        |    add eax, DWORD PTR [rbp-28]
+        |    jo .L1
+        |    mov DWORD PTR [rbp-16], eax
+        |    jmp .L0
+        |.L1:
+        |    mov eax, DWORD PTR [rbp-12]
+        |    add rsp, 32
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_range_bounds_see_the_binding_that_the_loop_variable_shadows() {
+    let program = compile(
+        r#"
+        |main :: () -> i32 {
+        |    i := 3;
+        |    mut n := 0;
+        |    for i : 0..i {
+        |        n += 1;
+        |    }
+        |    n
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov eax, 3
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov eax, 0
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, 0
+        |    mov DWORD PTR [rbp-12], eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    mov DWORD PTR [rbp-16], eax
+        |.L0:
+        |    mov eax, DWORD PTR [rbp-12]
+        |    cmp eax, DWORD PTR [rbp-16]
+        |    jge .L1
+        |    mov eax, 1
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    add eax, ecx
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, DWORD PTR [rbp-12]
+        |    add eax, 1
+        |    jo .L1
+        |    mov DWORD PTR [rbp-12], eax
+        |    jmp .L0
+        |.L1:
+        |    mov eax, DWORD PTR [rbp-8]
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_descending_ranges() {
+    let program = compile(
+        r#"
+        |main :: () {
+        |    for i : 10..0 by -2 {}
+        |    for j : 3u8..=0 by -1 {}
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov eax, 10
+        |    mov DWORD PTR [rbp-4], eax
+        |.L0:
+        |    mov eax, DWORD PTR [rbp-4]
+        |    cmp eax, 0
+        |    jle .L1
+        |    mov eax, DWORD PTR [rbp-4]
+        |    sub eax, 2
+        |    jo .L1
+        |    mov DWORD PTR [rbp-4], eax
+        |    jmp .L0
+        |.L1:
+        |    mov al, 3
+        |    mov BYTE PTR [rbp-5], al
+        |.L2:
+        |    mov al, BYTE PTR [rbp-5]
+        |    cmp al, 0
+        |    jb .L3
+        |    mov al, BYTE PTR [rbp-5]
+        |    sub al, 1
+        |    jc .L3
+        |    mov BYTE PTR [rbp-5], al
+        |    jmp .L2
+        |.L3:
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_range_up_to_the_maximum_of_its_type() {
+    let program = compile(
+        r#"
+        |main :: () -> u8 {
+        |    mut last := 0u8;
+        |    for i : 250u8..=255 {
+        |        if i == 252 {
+        |            continue
+        |        }
+        |        last = i;
+        |    }
+        |    last
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov al, 0
+        |    mov BYTE PTR [rbp-1], al
+        |    mov al, 250
+        |    mov BYTE PTR [rbp-2], al
+        |.L0:
+        |    mov al, BYTE PTR [rbp-2]
+        |    cmp al, 255
+        |    ja .L1
+        |    mov al, BYTE PTR [rbp-2]
+        |    mov BYTE PTR [rbp-3], al
+        |    mov al, 252
+        |    mov cl, al
+        |    mov al, BYTE PTR [rbp-3]
+        |    cmp al, cl
+        |    jne .L2
+        |    jmp .L3
+        |.L2:
+        |    mov al, BYTE PTR [rbp-2]
+        |    mov BYTE PTR [rbp-1], al
+        |.L3:
+        |    mov al, BYTE PTR [rbp-2]
+        |    add al, 1
+        |    jc .L1
+        |    mov BYTE PTR [rbp-2], al
+        |    jmp .L0
+        |.L1:
+        |    mov al, BYTE PTR [rbp-1]
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_step_of_wrong_type() {
+    check_diagnostics(
+        r#"
+        |main :: (a: u8) {
+        |    for i : 0..10 by true {}
+        |    for j : 0u8..a by 256 {}
+        |}
+        |"#,
+        &[
+            ("mismatched types: expected `i32`, found `bool`", "true"),
+            ("integer constant is out of range for `u8`", "256"),
+        ],
+    );
+}
+
+#[test]
+fn test_step_that_is_not_positive() {
+    check_diagnostics(
+        r#"
+        |STEP :: 2 - 2
+        |COUNT :: count_by(0 - 1)
+        |
+        |count_by :: (step: i32) -> i32 {
+        |    mut count := 0;
+        |    for i : 0..10 by step {
+        |        count += 1;
+        |    }
+        |    count
+        |}
+        |
+        |main :: () {
+        |    for i : 0..10 by 0 {}
+        |    for j : 10..=0 by STEP {}
+        |    for k : 0..10 by count_by(1) - 10 {}
+        |    for l : 0..10 by 0 - 2 {}
+        |}
+        |"#,
+        &[
+            (
+                "the step of a range must be positive, but it's `-1`",
+                "step",
+            ),
+            ("the step of a range must be positive, but it's `0`", "0"),
+            ("the step of a range must be positive, but it's `0`", "STEP"),
+            (
+                "the step of a range must be positive, but it's `0`",
+                "count_by(1) - 10",
+            ),
+            (
+                "the step of a range must be positive, but it's `-2`",
+                "0 - 2",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_step_known_only_at_run_time_makes_the_range_empty_unless_positive() {
+    let program = compile(
+        r#"
+        |count :: (step: u8) {
+        |    for i : 0u8..10 by step {}
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |count:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov BYTE PTR [rbp-1], dil
+        |    mov al, 0
+        |    mov BYTE PTR [rbp-2], al
+        |    mov al, BYTE PTR [rbp-1]
+        |    mov BYTE PTR [rbp-3], al
+        |    cmp BYTE PTR [rbp-3], 0
+        |    je .L1
+        |.L0:
+        |    mov al, BYTE PTR [rbp-2]
+        |    cmp al, 10
+        |    jae .L1
+        |    mov al, BYTE PTR [rbp-2]
+        |    add al, BYTE PTR [rbp-3]
+        |    jc .L1
+        |    mov BYTE PTR [rbp-2], al
+        |    jmp .L0
+        |.L1:
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_range_bound_that_never_has_a_value() {
+    check_diagnostics(
+        r#"
+        |main :: () -> i32 {
+        |    for i : (return 7)..10 {}
+        |    for j : 0..(return 8) {}
+        |    0
+        |}
+        |"#,
+        &[
+            ("the start of a range never has a value", "return 7"),
+            ("the end of a range never has a value", "return 8"),
+        ],
+    );
+}
@@ -1,4 +1,4 @@
-use crate::tests::{check, compile};
+use crate::tests::{check, check_diagnostics, compile};
 
 #[test]
 fn test_call_previously_defined_function() {
@@ -61,3 +61,147 @@ fn test_call_later_defined_function() {
         |"#,
     );
 }
+
+#[test]
+fn test_call_passes_arguments_in_registers() {
+    let program = compile(
+        r#"
+        |first :: (a: i32, b: i32) -> i32 {
+        |    a
+        |}
+        |
+        |main :: () -> i32 {
+        |    first(1, 2 + 3,)
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |first:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov DWORD PTR [rbp-8], esi
+        |    mov eax, DWORD PTR [rbp-4]
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov eax, 1
+        |    mov DWORD PTR [rbp-4], eax      ; first argument
+        |
+        |    mov eax, 2
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, 3
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    add eax, ecx
+        |    mov DWORD PTR [rbp-12], eax     ; second argument
+        |
+        |    mov edi, DWORD PTR [rbp-4]
+        |    mov esi, DWORD PTR [rbp-12]
+        |    call first
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_call_passes_arguments_after_the_sixth_on_the_stack() {
+    let program = compile(
+        r#"
+        |main :: () {
+        |    foo(1, 2, 3, 4, 5, 6, 7, 8)
+        |}
+        |
+        |foo :: (a: i32, b: i32, c: i32, d: i32, e: i32, f: i32, g: i32, h: i32) {}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 48                     ; 32 bytes of locals, 16 of stack arguments
+        |
+        |    mov eax, 1
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov eax, 2
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, 3
+        |    mov DWORD PTR [rbp-12], eax
+        |    mov eax, 4
+        |    mov DWORD PTR [rbp-16], eax
+        |    mov eax, 5
+        |    mov DWORD PTR [rbp-20], eax
+        |    mov eax, 6
+        |    mov DWORD PTR [rbp-24], eax
+        |    mov eax, 7
+        |    mov DWORD PTR [rbp-28], eax
+        |    mov eax, 8
+        |    mov DWORD PTR [rbp-32], eax
+        |
+        |    mov eax, DWORD PTR [rbp-28]     ; seventh argument goes right at rsp
+        |    mov DWORD PTR [rsp], eax
+        |    mov eax, DWORD PTR [rbp-32]
+        |    mov DWORD PTR [rsp+8], eax
+        |
+        |    mov edi, DWORD PTR [rbp-4]
+        |    mov esi, DWORD PTR [rbp-8]
+        |    mov edx, DWORD PTR [rbp-12]
+        |    mov ecx, DWORD PTR [rbp-16]
+        |    mov r8d, DWORD PTR [rbp-20]
+        |    mov r9d, DWORD PTR [rbp-24]
+        |    call foo
+        |
+        |    add rsp, 48
+        |    pop rbp
+        |    ret
+        |foo:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 32
+        |
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov DWORD PTR [rbp-8], esi
+        |    mov DWORD PTR [rbp-12], edx
+        |    mov DWORD PTR [rbp-16], ecx
+        |    mov DWORD PTR [rbp-20], r8d
+        |    mov DWORD PTR [rbp-24], r9d
+        |
+        |    add rsp, 32
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_call_with_wrong_argument_count() {
+    check_diagnostics(
+        r#"
+        |foo :: (a: i32) {}
+        |
+        |main :: () {
+        |    foo(1, 2)
+        |}
+        |"#,
+        &[(
+            "function `foo` takes 1 argument(s) but 2 were supplied",
+            "foo(1, 2)",
+        )],
+    );
+}
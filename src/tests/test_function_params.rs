@@ -0,0 +1,90 @@
+use crate::tests::{check, check_diagnostics, compile};
+
+#[test]
+fn test_parameters_are_spilled_from_registers() {
+    let program = compile(
+        r#"
+        |add :: (a: i32, b: i32,) -> i32 {
+        |    a + b
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |add:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov DWORD PTR [rbp-4], edi      ; a
+        |    mov DWORD PTR [rbp-8], esi      ; b
+        |
+        |    mov eax, DWORD PTR [rbp-4]
+        |    mov DWORD PTR [rbp-12], eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-12]
+        |    add eax, ecx
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_parameters_after_the_sixth_are_read_from_the_caller_frame() {
+    let program = compile(
+        r#"
+        |last :: (a: i32, b: i32, c: i32, d: i32, e: i32, f: i32, g: i32, h: i32) -> i32 {
+        |    f - h
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |last:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 32
+        |
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov DWORD PTR [rbp-8], esi
+        |    mov DWORD PTR [rbp-12], edx
+        |    mov DWORD PTR [rbp-16], ecx
+        |    mov DWORD PTR [rbp-20], r8d
+        |    mov DWORD PTR [rbp-24], r9d
+        |
+        |    mov eax, DWORD PTR [rbp-24]     ; f
+        |    mov DWORD PTR [rbp-28], eax
+        |    mov eax, DWORD PTR [rbp+24]     ; h, the eighth argument
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-28]
+        |    sub eax, ecx
+        |
+        |    add rsp, 32
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_parameter_without_type() {
+    check_diagnostics(
+        r#"
+        |add :: (a: i32, b) -> i32 {
+        |    a
+        |}
+        |"#,
+        &[(
+            "expected `:` followed by the parameter's type, found `)`",
+            ")",
+        )],
+    );
+}
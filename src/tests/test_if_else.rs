@@ -1,11 +1,11 @@
-use crate::tests::{check, compile};
+use crate::tests::{check, check_rendered_diagnostics, compile};
 
 #[test]
 fn test_if_else_with_basic_expressions() {
     let program = compile(
         r#"
-        |main :: () -> i32 {
-        |    if 1 {
+        |main :: (a: bool) -> i32 {
+        |    if a {
         |        1
         |    } else {
         |        0
@@ -20,7 +20,9 @@ fn test_if_else_with_basic_expressions() {
         |main:
         |    push rbp
         |    mov rbp, rsp
-        |    mov eax, 1
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov eax, DWORD PTR [rbp-4]
         |    cmp eax, 0
         |    je .L0
         |    mov eax, 1
@@ -30,6 +32,7 @@ fn test_if_else_with_basic_expressions() {
         |    mov eax, 0
         |
         |.L1:
+        |    add rsp, 16
         |    pop rbp
         |    ret
         |"#,
@@ -40,9 +43,9 @@ fn test_if_else_with_basic_expressions() {
 fn test_if_else_inside_another() {
     let program = compile(
         r#"
-        |main :: () -> i32 {
-        |    if 1 {
-        |        if 2 { 3 } else { 4 }
+        |main :: (a: bool, b: bool) -> i32 {
+        |    if a {
+        |        if b { 3 } else { 4 }
         |    } else {
         |        0
         |    }
@@ -56,10 +59,13 @@ fn test_if_else_inside_another() {
         |main:
         |    push rbp
         |    mov rbp, rsp
-        |    mov eax, 1
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov DWORD PTR [rbp-8], esi
+        |    mov eax, DWORD PTR [rbp-4]
         |    cmp eax, 0
         |    je .L0
-        |    mov eax, 2
+        |    mov eax, DWORD PTR [rbp-8]
         |    cmp eax, 0
         |    je .L1
         |    mov eax, 3
@@ -71,6 +77,7 @@ fn test_if_else_inside_another() {
         |.L0:
         |    mov eax, 0
         |.L3:
+        |    add rsp, 16
         |    pop rbp
         |    ret
         |"#,
@@ -81,8 +88,8 @@ fn test_if_else_inside_another() {
 fn test_if_without_else() {
     let program = compile(
         r#"
-        |main :: () -> i32 {
-        |    if 1 {
+        |main :: (a: bool) -> i32 {
+        |    if a {
         |        1
         |    };
         |
@@ -97,12 +104,15 @@ fn test_if_without_else() {
         |main:
         |    push rbp
         |    mov rbp, rsp
-        |    mov eax, 1
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov eax, DWORD PTR [rbp-4]
         |    cmp eax, 0
         |    je .L0
         |    mov eax, 1
         |.L0:
         |    mov eax, 0
+        |    add rsp, 16
         |    pop rbp
         |    ret
         |"#,
@@ -113,8 +123,8 @@ fn test_if_without_else() {
 fn test_multi_exprs_inside_if_body() {
     let program = compile(
         r#"
-        |main :: () -> i32 {
-        |    if 1 {
+        |main :: (a: bool) -> i32 {
+        |    if a {
         |        1;
         |        2;
         |        3
@@ -132,7 +142,9 @@ fn test_multi_exprs_inside_if_body() {
         |main:
         |    push rbp
         |    mov rbp, rsp
-        |    mov eax, 1
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov eax, DWORD PTR [rbp-4]
         |    cmp eax, 0
         |    je .L0
         |    mov eax, 1
@@ -143,6 +155,7 @@ fn test_multi_exprs_inside_if_body() {
         |    mov eax, 4
         |    mov eax, 5
         |.L1:
+        |    add rsp, 16
         |    pop rbp
         |    ret
         |"#,
@@ -153,12 +166,12 @@ fn test_multi_exprs_inside_if_body() {
 fn test_chained_if_else() {
     let program = compile(
         r#"
-        |main :: () -> i32 {
-        |    if 10 {
+        |main :: (a: bool, b: bool, c: bool) -> i32 {
+        |    if a {
         |        1
-        |    } else if 20 {
+        |    } else if b {
         |        2
-        |    } else if 30 {
+        |    } else if c {
         |        3
         |    } else {
         |        4
@@ -173,21 +186,25 @@ fn test_chained_if_else() {
         |main:
         |    push rbp
         |    mov rbp, rsp
-        |    mov eax, 10
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov DWORD PTR [rbp-8], esi
+        |    mov DWORD PTR [rbp-12], edx
+        |    mov eax, DWORD PTR [rbp-4]
         |    cmp eax, 0
         |    je .L0
         |    mov eax, 1
         |    jmp .L3
         |
         |.L0:
-        |    mov eax, 20
+        |    mov eax, DWORD PTR [rbp-8]
         |    cmp eax, 0
         |    je .L1
         |    mov eax, 2
         |    jmp .L3
         |
         |.L1:
-        |    mov eax, 30
+        |    mov eax, DWORD PTR [rbp-12]
         |    cmp eax, 0
         |    je .L2
         |    mov eax, 3
@@ -196,6 +213,7 @@ fn test_chained_if_else() {
         |.L2:
         |    mov eax, 4
         |.L3:
+        |    add rsp, 16
         |    pop rbp
         |    ret
         |"#,
@@ -206,12 +224,12 @@ fn test_chained_if_else() {
 fn test_chained_if_else_without_final_else() {
     let program = compile(
         r#"
-        |main :: () -> i32 {
-        |    if 10 {
+        |main :: (a: bool, b: bool, c: bool) {
+        |    if a {
         |        1
-        |    } else if 20 {
+        |    } else if b {
         |        2
-        |    } else if 30 {
+        |    } else if c {
         |        3
         |    }
         |}
@@ -224,29 +242,120 @@ fn test_chained_if_else_without_final_else() {
         |main:
         |    push rbp
         |    mov rbp, rsp
-        |    mov eax, 10
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov DWORD PTR [rbp-8], esi
+        |    mov DWORD PTR [rbp-12], edx
+        |    mov eax, DWORD PTR [rbp-4]
         |    cmp eax, 0
         |    je .L0
         |    mov eax, 1
         |    jmp .L2
         |
         |.L0:
-        |    mov eax, 20
+        |    mov eax, DWORD PTR [rbp-8]
         |    cmp eax, 0
         |    je .L1
         |    mov eax, 2
         |    jmp .L2
         |
         |.L1:
-        |    mov eax, 30
+        |    mov eax, DWORD PTR [rbp-12]
         |    cmp eax, 0
         |    je .L2
         |    mov eax, 3
         |    jmp .L2
         |
         |.L2:
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_if_as_value() {
+    let program = compile(
+        r#"
+        |main :: (c: bool, d: bool) -> i64 {
+        |    x := if c { 1i64 } else if d { return 5 } else { 3 };
+        |    mut y := 2;
+        |    y = if d { y } else { 7 };
+        |    if c { x } else { y as i64 }
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 32
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov DWORD PTR [rbp-8], esi
+        |    mov eax, DWORD PTR [rbp-4]
+        |    cmp eax, 0
+        |    je .L0
+        |    mov rax, 1
+        |    jmp .L3
+        |.L0:
+        |    mov eax, DWORD PTR [rbp-8]
+        |    cmp eax, 0
+        |    je .L1
+        |    mov rax, 5
+        |    jmp .L2
+        |    jmp .L3
+        |.L1:
+        |    mov rax, 3
+        |.L3:
+        |    mov QWORD PTR [rbp-16], rax
+        |    mov eax, 2
+        |    mov DWORD PTR [rbp-20], eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    cmp eax, 0
+        |    je .L4
+        |    mov eax, DWORD PTR [rbp-20]
+        |    jmp .L5
+        |.L4:
+        |    mov eax, 7
+        |.L5:
+        |    mov DWORD PTR [rbp-20], eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    cmp eax, 0
+        |    je .L6
+        |    mov rax, QWORD PTR [rbp-16]
+        |    jmp .L7
+        |.L6:
+        |    mov eax, DWORD PTR [rbp-20]
+        |    movsxd rax, eax
+        |.L7:
+        |.L2:
+        |    add rsp, 32
         |    pop rbp
         |    ret
         |"#,
     );
 }
+
+#[test]
+fn test_used_value_of_if_without_else() {
+    check_rendered_diagnostics(
+        r#"
+        |main :: (a: bool) -> i32 {
+        |    if a { 1 };
+        |    x := if a { 2 };
+        |    x + 1
+        |}
+        |"#,
+        r#"
+        |error: `if` without `else` can't give a value of type `i32`
+        | --> main.bit:3:17
+        |  |
+        |3 |     x := if a { 2 };
+        |  |                 ^
+        |  = help: add an `else` that gives a value for when no condition holds"#,
+    );
+}
@@ -0,0 +1,355 @@
+use crate::tests::{check, check_diagnostics, check_rendered_diagnostics, compile};
+
+#[test]
+fn test_64_bit_arithmetic() {
+    let program = compile(
+        r#"
+        |main :: (a: i64, b: i64) -> i64 {
+        |    a + b * 2
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 32
+        |    mov QWORD PTR [rbp-8], rdi
+        |    mov QWORD PTR [rbp-16], rsi
+        |
+        |    mov rax, QWORD PTR [rbp-8]
+        |    mov QWORD PTR [rbp-24], rax
+        |
+        |    mov rax, QWORD PTR [rbp-16]
+        |    mov QWORD PTR [rbp-32], rax
+        |    mov rax, 2                      ; the constant is inferred to be an i64
+        |    mov rcx, rax
+        |    mov rax, QWORD PTR [rbp-32]
+        |    imul rax, rcx
+        |
+        |    mov rcx, rax
+        |    mov rax, QWORD PTR [rbp-24]
+        |    add rax, rcx
+        |
+        |    add rsp, 32
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_unsigned_byte_division() {
+    let program = compile(
+        r#"
+        |main :: (x: u8, y: u8) -> u8 {
+        |    x / y
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov BYTE PTR [rbp-1], dil
+        |    mov BYTE PTR [rbp-2], sil
+        |
+        |    mov al, BYTE PTR [rbp-1]
+        |    mov BYTE PTR [rbp-3], al
+        |    mov al, BYTE PTR [rbp-2]
+        |    mov cl, al
+        |    mov al, BYTE PTR [rbp-3]
+        |
+        |    movzx eax, al
+        |    movzx ecx, cl
+        |    xor edx, edx
+        |    div ecx
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_unsigned_comparison() {
+    let program = compile(
+        r#"
+        |main :: (x: u16, y: u16) -> bool {
+        |    x < y
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov WORD PTR [rbp-2], di
+        |    mov WORD PTR [rbp-4], si
+        |
+        |    mov ax, WORD PTR [rbp-2]
+        |    mov WORD PTR [rbp-6], ax
+        |    mov ax, WORD PTR [rbp-4]
+        |    mov cx, ax
+        |    mov ax, WORD PTR [rbp-6]
+        |    cmp ax, cx
+        |    setb al
+        |    movzx eax, al
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_casts_extend_by_the_operand_signedness() {
+    let program = compile(
+        r#"
+        |main :: (x: i16, y: u32) -> u64 {
+        |    a := x as i64;
+        |    b := y as u64;
+        |    b
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 32
+        |    mov WORD PTR [rbp-2], di
+        |    mov DWORD PTR [rbp-8], esi
+        |
+        |    mov ax, WORD PTR [rbp-2]
+        |    movsx rax, ax
+        |    mov QWORD PTR [rbp-16], rax
+        |
+        |    mov eax, DWORD PTR [rbp-8]
+        |    mov eax, eax                    ; clears the upper half of rax
+        |    mov QWORD PTR [rbp-24], rax
+        |
+        |    mov rax, QWORD PTR [rbp-24]
+        |    add rsp, 32
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_constant_suffixes() {
+    let program = compile(
+        r#"
+        |main :: () -> i8 {
+        |    a := 255u8;
+        |    b := 18446744073709551615u64;
+        |    -128i8
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov al, 255
+        |    mov BYTE PTR [rbp-1], al
+        |
+        |    mov rax, -1
+        |    mov QWORD PTR [rbp-16], rax
+        |
+        |    mov al, -128
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_minimum_values_of_signed_types() {
+    let program = compile(
+        r#"
+        |main :: () -> i64 {
+        |    a := -2147483648;
+        |    -9223372036854775808
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov eax, -2147483648
+        |    mov DWORD PTR [rbp-4], eax
+        |
+        |    mov rax, -9223372036854775808
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_constant_too_large_for_its_type() {
+    check_rendered_diagnostics(
+        r#"
+        |main :: () -> i32 {
+        |    3000000000
+        |}
+        |"#,
+        r#"
+        |error: integer constant is out of range for `i32`
+        | --> main.bit:2:5
+        |  |
+        |2 |     3000000000
+        |  |     ^^^^^^^^^^
+        |  = note: the range of `i32` is `-2147483648..=2147483647`"#,
+    );
+}
+
+#[test]
+fn test_constants_out_of_range() {
+    check_diagnostics(
+        r#"
+        |foo :: (x: u8) {}
+        |
+        |main :: () {
+        |    foo(256);
+        |    a := 2147483648;
+        |    b := -129i8;
+        |    c := -1u32;
+        |}
+        |"#,
+        &[
+            ("integer constant is out of range for `u8`", "256"),
+            ("integer constant is out of range for `i32`", "2147483648"),
+            ("integer constant is out of range for `i8`", "-129i8"),
+            ("cannot apply unary operator `-` to type `u32`", "-1u32"),
+        ],
+    );
+}
+
+#[test]
+fn test_invalid_constant_suffix() {
+    check_diagnostics(
+        r#"
+        |main :: () {
+        |    x := 12xyz;
+        |}
+        |"#,
+        &[("invalid suffix `xyz` for an integer constant", "12xyz")],
+    );
+}
+
+#[test]
+fn test_constants_in_other_radixes() {
+    let program = compile(
+        r#"
+        |main :: () -> u32 {
+        |    a := 0xff_u8;
+        |    b := 0o17;
+        |    c := 0b1010_1010;
+        |    1_000_000
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov al, 255
+        |    mov BYTE PTR [rbp-1], al
+        |
+        |    mov eax, 15
+        |    mov DWORD PTR [rbp-8], eax
+        |
+        |    mov eax, 170
+        |    mov DWORD PTR [rbp-12], eax
+        |
+        |    mov eax, 1000000
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_malformed_constants() {
+    check_diagnostics(
+        r#"
+        |main :: () {
+        |    a := 0x;
+        |    b := 0b102;
+        |    c := 0o78;
+        |    d := 007;
+        |    e := 0x1_0000_0000_0000_0000;
+        |}
+        |"#,
+        &[
+            ("expected digits after the `0x` prefix", "0x"),
+            ("invalid digit `2` in a binary constant", "0b102"),
+            ("invalid digit `8` in an octal constant", "0o78"),
+            ("leading zeros are not allowed in a decimal constant", "007"),
+            (
+                "integer constant is too large for any integer type",
+                "0x1_0000_0000_0000_0000",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_integer_types_dont_mix() {
+    check_diagnostics(
+        r#"
+        |main :: (a: i64, b: u8, c: bool) -> i32 {
+        |    a + b;
+        |    c as i8;
+        |    a as bool;
+        |    a
+        |}
+        |"#,
+        &[
+            ("mismatched types: expected `i64`, found `u8`", "b"),
+            ("cannot cast `i64` as `bool`", "a as bool"),
+            ("mismatched types: expected `i32`, found `i64`", "a"),
+        ],
+    );
+}
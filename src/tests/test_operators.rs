@@ -0,0 +1,238 @@
+use crate::tests::{check, compile};
+
+#[test]
+fn test_product_binds_tighter_than_factor() {
+    let program = compile(
+        r#"
+        |main :: () -> i32 {
+        |    1 + 2 * 3
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov eax, 1
+        |    mov DWORD PTR [rbp-4], eax      ; spill lhs of `+`
+        |
+        |    mov eax, 2
+        |    mov DWORD PTR [rbp-8], eax      ; spill lhs of `*`
+        |    mov eax, 3
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    imul eax, ecx
+        |
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    add eax, ecx
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_subtraction_is_left_associative() {
+    let program = compile(
+        r#"
+        |main :: () -> i32 {
+        |    10 - 3 - 2
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov eax, 10
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov eax, 3
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    sub eax, ecx                    ; 10 - 3
+        |
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, 2
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    sub eax, ecx                    ; (10 - 3) - 2
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_division_sign_extends_dividend() {
+    let program = compile(
+        r#"
+        |main :: () -> i32 {
+        |    7 / 2
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov eax, 7
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov eax, 2
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    cdq
+        |    idiv ecx
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_shifts_take_count_in_cl() {
+    let program = compile(
+        r#"
+        |main :: () -> i32 {
+        |    1 << 4 >> 2
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov eax, 1
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov eax, 4
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    sal eax, cl
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, 2
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    sar eax, cl
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_relational_binds_tighter_than_equality() {
+    let program = compile(
+        r#"
+        |main :: () -> bool {
+        |    1 + 2 <= 4 == true
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov eax, 1
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov eax, 2
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    add eax, ecx
+        |
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, 4
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    cmp eax, ecx
+        |    setle al
+        |    movzx eax, al
+        |
+        |    mov DWORD PTR [rbp-12], eax
+        |    mov eax, 1
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-12]
+        |    cmp eax, ecx
+        |    sete al
+        |    movzx eax, al
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_prefix_operators() {
+    let program = compile(
+        r#"
+        |main :: () -> bool {
+        |    x := 5;
+        |    is_negative := -x < 0;
+        |    !is_negative
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov eax, 5
+        |    mov DWORD PTR [rbp-4], eax
+        |
+        |    mov eax, DWORD PTR [rbp-4]
+        |    neg eax
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, 0
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    cmp eax, ecx
+        |    setl al
+        |    movzx eax, al
+        |    mov DWORD PTR [rbp-12], eax
+        |
+        |    mov eax, DWORD PTR [rbp-12]
+        |    cmp eax, 0
+        |    sete al
+        |    movzx eax, al
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
@@ -0,0 +1,89 @@
+use crate::tests::{check, check_diagnostics, compile};
+
+#[test]
+fn test_parentheses_override_precedence() {
+    let program = compile(
+        r#"
+        |main :: () -> i32 {
+        |    (1 + 2) * ((3))
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov eax, 1
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov eax, 2
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    add eax, ecx                    ; (1 + 2)
+        |
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, 3
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    imul eax, ecx
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_unit_constant_is_not_a_function_literal() {
+    let program = compile(
+        r#"
+        |main :: () {
+        |    ();
+        |    ( )
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_unclosed_parenthesized_expression() {
+    check_diagnostics(
+        r#"
+        |main :: () -> i32 {
+        |    (1 + 2, 3)
+        |}
+        |"#,
+        &[(
+            "expected `)` to close the parenthesized expression, found `,`",
+            ",",
+        )],
+    );
+}
+
+#[test]
+fn test_function_literal_parameter_without_type() {
+    check_diagnostics(
+        r#"
+        |main :: (x) -> i32 {
+        |    x
+        |}
+        |"#,
+        &[("expected a function literal, found `->`", "->")],
+    );
+}
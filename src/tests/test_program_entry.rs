@@ -0,0 +1,151 @@
+use crate::codegen::OutputKind;
+use crate::tests::{check, check_diagnostics_to, compile_to};
+
+#[test]
+fn test_complete_assembly_file() {
+    let program = compile_to(
+        r#"
+        |square :: (n: i32) -> i32 {
+        |    n * n
+        |}
+        |
+        |main :: () -> i32 {
+        |    square(3) - 2
+        |}
+        |"#,
+        OutputKind::File,
+    );
+
+    check(
+        program,
+        r#"
+        |    .intel_syntax noprefix
+        |    .text
+        |    .globl square
+        |square:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov eax, DWORD PTR [rbp-4]
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    imul eax, ecx
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |    .globl main
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov eax, 3
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov edi, DWORD PTR [rbp-4]
+        |    call square
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, 2
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    sub eax, ecx
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |    .section .note.GNU-stack,"",@progbits
+        |"#,
+    );
+}
+
+#[test]
+fn test_freestanding_file_with_main_returning_nothing() {
+    let program = compile_to(
+        r#"
+        |mut exits := 0
+        |
+        |main :: () {
+        |    exits += 1;
+        |    if exits > 1 {
+        |        return;
+        |    }
+        |}
+        |"#,
+        OutputKind::FreestandingFile,
+    );
+
+    check(
+        program,
+        r#"
+        |    .intel_syntax noprefix
+        |    .text
+        |    .globl main
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov eax, 1
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR exits[rip]
+        |    add eax, ecx
+        |    mov DWORD PTR exits[rip], eax
+        |    mov eax, DWORD PTR exits[rip]
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov eax, 1
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-4]
+        |    cmp eax, ecx
+        |    jle .L0
+        |    jmp .L1
+        |.L0:
+        |.L1:
+        |    mov eax, 0
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |    .globl _start
+        |_start:
+        |    call main
+        |    mov edi, eax
+        |    mov eax, 60
+        |    syscall
+        |    .bss
+        |    .align 4
+        |exits:
+        |    .zero 4
+        |    .section .note.GNU-stack,"",@progbits
+        |"#,
+    );
+}
+
+#[test]
+fn test_main_that_cannot_be_the_entry_point() {
+    check_diagnostics_to(
+        r#"
+        |main :: (argc: i32) -> i64 {
+        |    0
+        |}
+        |"#,
+        OutputKind::File,
+        &[
+            ("`main` can't take parameters", "main"),
+            ("`main` must return `i32` or `()`", "main"),
+        ],
+    );
+
+    check_diagnostics_to(
+        r#"
+        |main :: 3
+        |"#,
+        OutputKind::File,
+        &[("`main` must be a function", "main")],
+    );
+
+    check_diagnostics_to(
+        r#"
+        |helper :: () {}
+        |"#,
+        OutputKind::FreestandingFile,
+        &[("`_start` needs a `main` function to call", "")],
+    );
+}
@@ -0,0 +1,103 @@
+use crate::tests::{check, check_diagnostics, compile};
+
+#[test]
+fn test_early_return_jumps_to_epilogue() {
+    let program = compile(
+        r#"
+        |abs :: (x: i32) -> i32 {
+        |    if x < 0 {
+        |        return -x
+        |    };
+        |
+        |    x
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |abs:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |
+        |    mov eax, DWORD PTR [rbp-4]
+        |    mov DWORD PTR [rbp-8], eax
+        |    mov eax, 0
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR [rbp-8]
+        |    cmp eax, ecx
+        |    jge .L0
+        |
+        |    mov eax, DWORD PTR [rbp-4]
+        |    neg eax
+        |    jmp .L1                         ; return -x
+        |
+        |.L0:
+        |    mov eax, DWORD PTR [rbp-4]      ; trailing expression falls through
+        |
+        |.L1:                                ; epilogue
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_return_without_value_from_loop() {
+    let program = compile(
+        r#"
+        |main :: () {
+        |    for {
+        |        return;
+        |    }
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |.L0:
+        |    jmp .L2
+        |    jmp .L0
+        |.L1:
+        |.L2:
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_return_without_value_from_i32_function() {
+    check_diagnostics(
+        r#"
+        |main :: () -> i32 {
+        |    return
+        |}
+        |"#,
+        &[(
+            "`return` without a value in a function returning `i32`",
+            "return",
+        )],
+    );
+}
+
+#[test]
+fn test_return_with_value_from_unit_function() {
+    check_diagnostics(
+        r#"
+        |main :: () -> () {
+        |    return 1
+        |}
+        |"#,
+        &[("mismatched types: expected `()`, found `i32`", "1")],
+    );
+}
@@ -0,0 +1,106 @@
+use crate::compiler_context::CompilerContext;
+use crate::scanner::{Delim, Scanner, TokenKind};
+
+/// Scans the source code into the kind and text of each of its tokens.
+fn scan(source_code: &str) -> Vec<(TokenKind, String)> {
+    let context = CompilerContext::new(source_code.into());
+    let tokens = Scanner::new(&context).scan_all_tokens();
+
+    assert!(!context.has_errors(), "scanning failed");
+
+    tokens
+        .iter()
+        .map(|tok| {
+            let text = &context.get_source_code()[tok.span.start.0..tok.span.end.0];
+
+            (tok.kind, text.to_string())
+        })
+        .collect()
+}
+
+fn check_tokens(source_code: &str, expected_tokens: &[(TokenKind, &str)]) {
+    let expected_tokens = expected_tokens
+        .iter()
+        .map(|&(kind, text)| (kind, text.to_string()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(scan(source_code), expected_tokens);
+}
+
+#[test]
+fn test_equality_and_assignment_operators() {
+    check_tokens(
+        "a == b != c = d",
+        &[
+            (TokenKind::Identifier, "a"),
+            (TokenKind::EqualEqual, "=="),
+            (TokenKind::Identifier, "b"),
+            (TokenKind::ExclaEqual, "!="),
+            (TokenKind::Identifier, "c"),
+            (TokenKind::Equal, "="),
+            (TokenKind::Identifier, "d"),
+        ],
+    );
+}
+
+#[test]
+fn test_shift_and_comparison_operators() {
+    check_tokens(
+        "a << b >= c",
+        &[
+            (TokenKind::Identifier, "a"),
+            (TokenKind::LessLess, "<<"),
+            (TokenKind::Identifier, "b"),
+            (TokenKind::GreaterEqual, ">="),
+            (TokenKind::Identifier, "c"),
+        ],
+    );
+}
+
+#[test]
+fn test_unit_constant() {
+    check_tokens(
+        "() ( )",
+        &[
+            (TokenKind::UnitConstant, "()"),
+            (TokenKind::Open(Delim::Paren), "("),
+            (TokenKind::Closed(Delim::Paren), ")"),
+        ],
+    );
+}
+
+#[test]
+fn test_operators_sharing_a_prefix_take_the_longest_match() {
+    check_tokens(
+        "= == === = =",
+        &[
+            (TokenKind::Equal, "="),
+            (TokenKind::EqualEqual, "=="),
+            (TokenKind::EqualEqual, "=="),
+            (TokenKind::Equal, "="),
+            (TokenKind::Equal, "="),
+            (TokenKind::Equal, "="),
+        ],
+    );
+    check_tokens(
+        "< << <= <<< < <",
+        &[
+            (TokenKind::Less, "<"),
+            (TokenKind::LessLess, "<<"),
+            (TokenKind::LessEqual, "<="),
+            (TokenKind::LessLess, "<<"),
+            (TokenKind::Less, "<"),
+            (TokenKind::Less, "<"),
+            (TokenKind::Less, "<"),
+        ],
+    );
+    check_tokens(
+        "> >= > =",
+        &[
+            (TokenKind::Greater, ">"),
+            (TokenKind::GreaterEqual, ">="),
+            (TokenKind::Greater, ">"),
+            (TokenKind::Equal, "="),
+        ],
+    );
+}
@@ -0,0 +1,157 @@
+use crate::tests::{check, check_diagnostics, compile};
+
+#[test]
+fn test_constants_are_put_in_place_of_their_uses() {
+    let program = compile(
+        r#"
+        |answer :: 42
+        |limit :: -answer
+        |big :: 5_000_000_000i64
+        |enabled :: !false
+        |
+        |main :: () -> i64 {
+        |    if enabled {
+        |        big + limit as i64
+        |    } else {
+        |        0
+        |    }
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov eax, 1
+        |    cmp eax, 0
+        |    je .L0
+        |    mov rax, 5000000000
+        |    mov QWORD PTR [rbp-8], rax
+        |    mov eax, -42
+        |    movsxd rax, eax
+        |    mov rcx, rax
+        |    mov rax, QWORD PTR [rbp-8]
+        |    add rax, rcx
+        |    jmp .L1
+        |.L0:
+        |    mov rax, 0
+        |.L1:
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_mutable_globals() {
+    let program = compile(
+        r#"
+        |mut counter := 5
+        |mut total := 0i64
+        |mut flag := false
+        |
+        |bump :: (amount: i32) -> i32 {
+        |    counter += amount;
+        |    total = total + 1;
+        |    flag = true;
+        |    counter
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |bump:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |    mov DWORD PTR [rbp-4], edi
+        |    mov eax, DWORD PTR [rbp-4]
+        |    mov ecx, eax
+        |    mov eax, DWORD PTR counter[rip]
+        |    add eax, ecx
+        |    mov DWORD PTR counter[rip], eax
+        |    mov rax, QWORD PTR total[rip]
+        |    mov QWORD PTR [rbp-16], rax
+        |    mov rax, 1
+        |    mov rcx, rax
+        |    mov rax, QWORD PTR [rbp-16]
+        |    add rax, rcx
+        |    mov QWORD PTR total[rip], rax
+        |    mov eax, 1
+        |    mov DWORD PTR flag[rip], eax
+        |    mov eax, DWORD PTR counter[rip]
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |    .data
+        |    .align 4
+        |counter:
+        |    .long 5
+        |    .bss
+        |    .align 8
+        |total:
+        |    .zero 8
+        |    .align 4
+        |flag:
+        |    .zero 4
+        |"#,
+    );
+}
+
+#[test]
+fn test_constant_that_is_not_known_at_compile_time() {
+    check_diagnostics(
+        r#"
+        |mut start := 1
+        |end :: start
+        |"#,
+        &[("the value of `end` must be known at compile time", "start")],
+    );
+}
+
+#[test]
+fn test_constants_cannot_be_assigned_to() {
+    check_diagnostics(
+        r#"
+        |limit :: 10
+        |mut count := 0
+        |
+        |main :: () {
+        |    limit = 3;
+        |    count = true;
+        |}
+        |"#,
+        &[
+            (
+                "cannot assign to `limit`, as it is not mutable",
+                "limit = 3",
+            ),
+            ("mismatched types: expected `i32`, found `bool`", "true"),
+        ],
+    );
+}
+
+#[test]
+fn test_mutable_global_after_syntax_error_is_still_declared() {
+    check_diagnostics(
+        r#"
+        |limit :: 1 +
+        |mut count := 0
+        |
+        |main :: () {
+        |    count = true;
+        |}
+        |"#,
+        &[
+            ("expected an expression, found `mut`", "mut"),
+            ("mismatched types: expected `i32`, found `bool`", "true"),
+        ],
+    );
+}
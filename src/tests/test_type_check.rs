@@ -0,0 +1,149 @@
+use crate::tests::{check, check_diagnostics, compile};
+
+#[test]
+fn test_values_of_type_unit_take_no_stack_slot_or_register() {
+    let program = compile(
+        r#"
+        |foo :: () {}
+        |
+        |bar :: (a: (), b: i32) -> i32 {
+        |    a;
+        |    b
+        |}
+        |
+        |main :: () -> i32 {
+        |    x := foo();
+        |    bar(x, 7)
+        |}
+        |"#,
+    );
+
+    check(
+        program,
+        r#"
+        |foo:
+        |    push rbp
+        |    mov rbp, rsp
+        |    pop rbp
+        |    ret
+        |bar:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    mov DWORD PTR [rbp-4], edi       ; `b` is the first parameter passed
+        |    mov eax, DWORD PTR [rbp-4]
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |main:
+        |    push rbp
+        |    mov rbp, rsp
+        |    sub rsp, 16
+        |
+        |    call foo
+        |
+        |    mov eax, 7
+        |    mov DWORD PTR [rbp-4], eax
+        |    mov edi, DWORD PTR [rbp-4]
+        |    call bar
+        |
+        |    add rsp, 16
+        |    pop rbp
+        |    ret
+        |"#,
+    );
+}
+
+#[test]
+fn test_function_body_must_produce_its_return_type() {
+    check_diagnostics(
+        r#"
+        |foo :: () -> i32 {
+        |    x := 1;
+        |}
+        |"#,
+        &[("mismatched types: expected `i32`, found `()`", "x := 1;")],
+    );
+}
+
+#[test]
+fn test_function_that_always_returns_early() {
+    check_diagnostics(
+        r#"
+        |abs :: (x: i32) -> i32 {
+        |    if x < 0 {
+        |        return -x;
+        |    }
+        |    return x;
+        |}
+        |"#,
+        &[],
+    );
+}
+
+#[test]
+fn test_if_branches_with_incompatible_types() {
+    check_diagnostics(
+        r#"
+        |foo :: () {}
+        |
+        |main :: () -> i32 {
+        |    if true {
+        |        1
+        |    } else {
+        |        foo()
+        |    }
+        |}
+        |"#,
+        &[(
+            "`if` and `else` have incompatible types: expected `i32`, found `()`",
+            "foo()",
+        )],
+    );
+}
+
+#[test]
+fn test_argument_of_wrong_type() {
+    check_diagnostics(
+        r#"
+        |foo :: (a: i32) {}
+        |
+        |main :: () {
+        |    foo(())
+        |}
+        |"#,
+        &[("mismatched types: expected `i32`, found `()`", "()")],
+    );
+}
+
+#[test]
+fn test_operand_of_wrong_type() {
+    check_diagnostics(
+        r#"
+        |foo :: () {}
+        |
+        |main :: () -> i32 {
+        |    -foo() + 1
+        |}
+        |"#,
+        &[("cannot apply unary operator `-` to type `()`", "-foo()")],
+    );
+}
+
+#[test]
+fn test_function_used_as_a_value() {
+    check_diagnostics(
+        r#"
+        |foo :: (a: i32) -> i32 {
+        |    a
+        |}
+        |
+        |main :: () {
+        |    f := foo;
+        |}
+        |"#,
+        &[("function `foo` can't be used as a value", "foo")],
+    );
+}
@@ -0,0 +1,959 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{
+    AssignExpr, BinaryExpr, BinaryOp, BreakExpr, CastExpr, CompoundExpr, Const, Expr, ExprKind,
+    FnCallExpr, ForExpr, ForIteration, Function, IfExpr, IntType, NodeId, Program, Type, UnaryExpr,
+    UnaryOp,
+};
+use crate::compiler_context::CompilerContext;
+use crate::diagnostic::Diagnostic;
+use crate::interner::Symbol;
+use crate::labels::LoopTargets;
+use crate::resolver::{Definition, NameResolution};
+use crate::scanner::Span;
+
+/// The type of an expression, as found by the type checker.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum Ty {
+    /// A type that can be written in the source code.
+    Value(Type),
+    /// The type of the function literal with this id. Each function has a type
+    /// of its own.
+    Function(NodeId),
+    /// The type of expressions that never produce a value, such as `return`. It
+    /// fits where any type is expected.
+    Never,
+    /// The type of expressions that have an error already reported. It fits
+    /// where any type is expected, so that the error doesn't cascade.
+    Error,
+}
+
+impl Ty {
+    const UNIT: Ty = Ty::Value(Type::Unit);
+    const BOOL: Ty = Ty::Value(Type::Bool);
+
+    /// Tells whether a value of this type can be used where `expected_ty` is
+    /// expected.
+    fn fits(self, expected_ty: Ty) -> bool {
+        self == expected_ty
+            || matches!(self, Ty::Never | Ty::Error)
+            || matches!(expected_ty, Ty::Never | Ty::Error)
+    }
+
+    fn int_type(self) -> Option<IntType> {
+        match self {
+            Ty::Value(Type::Int(int_type)) => Some(int_type),
+            _ => None,
+        }
+    }
+}
+
+/// The type of every expression in the program, keyed by its id.
+#[derive(Default)]
+pub(crate) struct TypeTable {
+    ty_by_node: HashMap<NodeId, Ty>,
+}
+
+impl TypeTable {
+    pub(crate) fn get(&self, node_id: NodeId) -> Ty {
+        self.ty_by_node[&node_id]
+    }
+}
+
+/// A loop whose body is being checked.
+#[derive(Clone, Copy)]
+struct Loop {
+    /// Whether the loop has no condition, so that it only ends through a
+    /// `break`, which may give it a value.
+    is_infinite: bool,
+    /// The type that the loop's surroundings expect, if known.
+    expected: Option<Ty>,
+    /// Type of the values that the loop's `break`s give it, once found.
+    break_ty: Option<Ty>,
+}
+
+pub(crate) struct TypeChecker<'ctx> {
+    ctx: &'ctx CompilerContext,
+    name_resolution: &'ctx NameResolution,
+    loop_targets: &'ctx LoopTargets,
+    function_by_id: HashMap<NodeId, Function<'ctx>>,
+    /// Span of the name of each top-level function, by the id of its function
+    /// literal.
+    decl_span_by_function: HashMap<NodeId, Span>,
+    ty_by_definition: HashMap<Definition, Ty>,
+    /// Value of each top-level constant and global, by its name, so that its
+    /// type can be found when it's first referred to.
+    value_by_symbol: HashMap<Symbol, &'ctx Expr<'ctx>>,
+    /// Names of the constants and globals whose values are being checked, to
+    /// find those that depend on themselves.
+    values_being_checked: HashSet<Symbol>,
+    /// Return type of the function being checked.
+    return_ty: Ty,
+    /// Loops around the expression being checked, by the id of their `for`
+    /// expression.
+    loop_by_id: HashMap<NodeId, Loop>,
+    /// Ids of the expressions whose value is thrown away, such as statements,
+    /// so that an `if` among them needs no `else`.
+    discarded_values: HashSet<NodeId>,
+    types: TypeTable,
+}
+
+impl<'ctx> TypeChecker<'ctx> {
+    pub(crate) fn new(
+        ctx: &'ctx CompilerContext,
+        name_resolution: &'ctx NameResolution,
+        loop_targets: &'ctx LoopTargets,
+    ) -> TypeChecker<'ctx> {
+        TypeChecker {
+            ctx,
+            name_resolution,
+            loop_targets,
+            function_by_id: HashMap::new(),
+            decl_span_by_function: HashMap::new(),
+            ty_by_definition: HashMap::new(),
+            value_by_symbol: HashMap::new(),
+            values_being_checked: HashSet::new(),
+            return_ty: Ty::UNIT,
+            loop_by_id: HashMap::new(),
+            discarded_values: HashSet::new(),
+            types: TypeTable::default(),
+        }
+    }
+
+    pub(crate) fn check_program(&mut self, program: Program<'ctx>) -> TypeTable {
+        // Functions can be called before they are declared, so their types are known
+        // upfront.
+        for decl in program.decls {
+            match decl.value.kind {
+                ExprKind::Function(function) => {
+                    self.function_by_id.insert(decl.value.id, function);
+                    self.decl_span_by_function
+                        .insert(decl.value.id, decl.identifier_span);
+
+                    self.ty_by_definition
+                        .entry(Definition::TopLevel(decl.identifier))
+                        .or_insert(Ty::Function(decl.value.id));
+                }
+                _ => {
+                    self.value_by_symbol
+                        .entry(decl.identifier)
+                        .or_insert(decl.value);
+                }
+            }
+        }
+
+        // The types of constants and globals are those of their values. As they can
+        // refer to each other in any order, each is checked when first referred to,
+        // if that comes before its turn here.
+        for decl in program.decls {
+            let is_checked = self.types.ty_by_node.contains_key(&decl.value.id);
+
+            if !matches!(decl.value.kind, ExprKind::Function(_)) && !is_checked {
+                self.check_top_level_value(decl.identifier, decl.value);
+            }
+        }
+
+        for decl in program.decls {
+            if matches!(decl.value.kind, ExprKind::Function(_)) {
+                self.check_expr(decl.value);
+            }
+        }
+
+        std::mem::take(&mut self.types)
+    }
+
+    fn check_expr(&mut self, expr: &'ctx Expr<'ctx>) -> Ty {
+        self.check_expr_expecting(expr, None)
+    }
+
+    /// Finds the type of an expression, given the type that its surroundings
+    /// expect, if known. Integer constants without a suffix take that type,
+    /// if it's an integer type.
+    fn check_expr_expecting(&mut self, expr: &'ctx Expr<'ctx>, expected: Option<Ty>) -> Ty {
+        let ty = match expr.kind {
+            ExprKind::Const(Const::Unit) => Ty::UNIT,
+            ExprKind::Const(Const::IntegerConstant { value, suffix }) => {
+                self.check_integer_constant(value, suffix, false, expected, expr.span)
+            }
+            ExprKind::Const(Const::BoolConstant { .. }) => Ty::BOOL,
+            ExprKind::BindRef(_) => self.ty_of_reference(expr.id, expr.span),
+            ExprKind::BindDef(bind_def) => {
+                let value_ty = self.check_expr(bind_def.value);
+                self.ty_by_definition
+                    .insert(Definition::Local(expr.id), value_ty);
+
+                Ty::UNIT
+            }
+            ExprKind::Assign(assign_expr) => {
+                self.check_assign_expr(expr, assign_expr);
+
+                Ty::UNIT
+            }
+            ExprKind::Function(function) => {
+                self.function_by_id.insert(expr.id, function);
+                self.check_function(expr, function);
+
+                Ty::Function(expr.id)
+            }
+            ExprKind::If(if_expr) => self.check_if_expr(expr, if_expr, expected),
+            ExprKind::For(for_expr) => self.check_for_expr(expr.id, for_expr, expected),
+            ExprKind::Break(break_expr) => {
+                self.check_break_expr(expr, break_expr);
+
+                Ty::Never
+            }
+            ExprKind::Continue(_) => Ty::Never,
+            ExprKind::Return(value) => {
+                self.check_return_expr(value, expr.span);
+
+                Ty::Never
+            }
+            ExprKind::Compound(compound_expr) => self.check_compound_expr(
+                compound_expr,
+                expected,
+                self.discarded_values.contains(&expr.id),
+            ),
+            ExprKind::Semi(expr) => {
+                self.discarded_values.insert(expr.id);
+                self.check_expr(expr);
+
+                Ty::UNIT
+            }
+            ExprKind::FnCall(fn_call_expr) => self.check_fn_call_expr(expr, fn_call_expr),
+            ExprKind::Binary(binary_expr) => self.check_binary_expr(binary_expr, expected),
+            ExprKind::Unary(unary_expr) => self.check_unary_expr(expr, unary_expr, expected),
+            ExprKind::Cast(cast_expr) => self.check_cast_expr(expr, cast_expr),
+            ExprKind::Error => Ty::Error,
+        };
+
+        self.types.ty_by_node.insert(expr.id, ty);
+
+        ty
+    }
+
+    fn check_function(&mut self, function_expr: &Expr, function: Function<'ctx>) {
+        for (index, param) in function.parameters.iter().enumerate() {
+            self.ty_by_definition.insert(
+                Definition::Param {
+                    function: function_expr.id,
+                    index,
+                },
+                Ty::Value(param.ty),
+            );
+        }
+
+        let return_ty = Ty::Value(function.return_type);
+        let outer_return_ty = std::mem::replace(&mut self.return_ty, return_ty);
+
+        let body_ty =
+            self.check_compound_expr(function.body, Some(return_ty), return_ty == Ty::UNIT);
+
+        // The value of a function returning `()` is just discarded, whatever its type.
+        if return_ty != Ty::UNIT {
+            let body_value_span = function
+                .body
+                .exprs
+                .last()
+                .map_or(function_expr.span, |tail_expr| tail_expr.span);
+
+            self.expect_ty(body_ty, return_ty, body_value_span);
+        }
+
+        self.return_ty = outer_return_ty;
+    }
+
+    fn check_if_expr(
+        &mut self,
+        if_expr_node: &Expr,
+        if_expr: IfExpr<'ctx>,
+        expected: Option<Ty>,
+    ) -> Ty {
+        let branches = std::iter::once((Some(if_expr.cond_expr), if_expr.true_branch))
+            .chain(
+                if_expr
+                    .else_if_branches
+                    .iter()
+                    .map(|branch| (Some(branch.cond_expr), branch.true_branch)),
+            )
+            .chain(
+                if_expr
+                    .final_branch
+                    .map(|final_branch| (None, final_branch)),
+            );
+
+        let mut branch_tys = vec![];
+        // Once a branch produces a value, the next ones are expected to produce the
+        // same type.
+        let mut branch_expected = expected;
+        let is_discarded = self.discarded_values.contains(&if_expr_node.id);
+
+        for (cond_expr, branch) in branches {
+            if let Some(cond_expr) = cond_expr {
+                self.check_cond_expr(cond_expr);
+            }
+
+            let branch_ty = self.check_compound_expr(branch, branch_expected, is_discarded);
+
+            if branch_expected.is_none() && matches!(branch_ty, Ty::Value(_)) {
+                branch_expected = Some(branch_ty);
+            }
+
+            branch_tys.push((branch_ty, branch));
+        }
+
+        // Without an `else`, there is no value when no condition holds, so the
+        // branches can't give one to be used either.
+        if if_expr.final_branch.is_none() {
+            let mut if_ty = Ty::UNIT;
+
+            for (branch_ty, branch) in branch_tys {
+                let gives_value =
+                    matches!(branch_ty, Ty::Value(_) | Ty::Function(_)) && branch_ty != Ty::UNIT;
+
+                if gives_value && !is_discarded {
+                    let tail_span = branch
+                        .exprs
+                        .last()
+                        .map_or(if_expr_node.span, |tail_expr| tail_expr.span);
+
+                    self.ctx.report(
+                        Diagnostic::error(
+                            format!(
+                                "`if` without `else` can't give a value of type `{}`",
+                                self.display_ty(branch_ty)
+                            ),
+                            tail_span,
+                        )
+                        .with_help("add an `else` that gives a value for when no condition holds"),
+                    );
+
+                    if_ty = Ty::Error;
+                }
+            }
+
+            return if_ty;
+        }
+
+        // The first branch that produces a value decides the type of the whole `if`.
+        let if_ty = branch_tys
+            .iter()
+            .map(|&(branch_ty, _)| branch_ty)
+            .find(|branch_ty| !matches!(branch_ty, Ty::Never | Ty::Error))
+            .unwrap_or(Ty::Never);
+
+        for (branch_ty, branch) in branch_tys {
+            if !branch_ty.fits(if_ty) {
+                let tail_span = branch
+                    .exprs
+                    .last()
+                    .map_or(if_expr_node.span, |tail_expr| tail_expr.span);
+
+                self.ctx.report(Diagnostic::error(
+                    format!(
+                        "`if` and `else` have incompatible types: expected `{}`, found `{}`",
+                        self.display_ty(if_ty),
+                        self.display_ty(branch_ty)
+                    ),
+                    tail_span,
+                ));
+            }
+        }
+
+        if_ty
+    }
+
+    /// Finds the type of a loop. A `for` without a condition has the type of
+    /// the values its `break`s give it, or never produces a value if it has
+    /// none, while the other loops have no value to give.
+    fn check_for_expr(
+        &mut self,
+        for_id: NodeId,
+        for_expr: ForExpr<'ctx>,
+        expected: Option<Ty>,
+    ) -> Ty {
+        match for_expr.iteration {
+            Some(ForIteration::Conditional { cond_expr }) => self.check_cond_expr(cond_expr),
+            Some(ForIteration::Iterative {
+                start_expr,
+                end_expr,
+                step_expr,
+                ..
+            }) => {
+                let mut bound_ty = self.check_operands(start_expr, end_expr, None, false);
+
+                // The loop variable takes the type of the bounds, so a bound that leaves
+                // early, such as through `return`, leaves it without one.
+                for (bound, bound_name) in [(start_expr, "start"), (end_expr, "end")] {
+                    if self.types.get(bound.id) == Ty::Never {
+                        self.ctx.report(Diagnostic::error(
+                            format!("the {} of a range never has a value", bound_name),
+                            bound.span,
+                        ));
+
+                        bound_ty = Ty::Error;
+                    }
+                }
+
+                if let Some(step_expr) = step_expr {
+                    let step_ty = self.check_expr_expecting(step_expr, Some(bound_ty));
+                    self.expect_ty(step_ty, bound_ty, step_expr.span);
+                }
+
+                self.ty_by_definition
+                    .insert(Definition::Local(for_id), bound_ty);
+            }
+            None => {}
+        }
+
+        self.loop_by_id.insert(
+            for_id,
+            Loop {
+                is_infinite: for_expr.iteration.is_none(),
+                expected,
+                break_ty: None,
+            },
+        );
+
+        self.check_compound_expr(for_expr.body, None, true);
+
+        let for_loop = self
+            .loop_by_id
+            .remove(&for_id)
+            .expect("the loop was inserted right before its body");
+
+        if for_loop.is_infinite {
+            for_loop.break_ty.unwrap_or(Ty::Never)
+        } else {
+            Ty::UNIT
+        }
+    }
+
+    /// Checks that every `break` of a loop gives it a value of the same type,
+    /// which is `()` for a `break` without a value.
+    fn check_break_expr(&mut self, break_expr_node: &Expr, break_expr: BreakExpr<'ctx>) {
+        let value = break_expr.value;
+        let span = break_expr_node.span;
+
+        // A `break` without a loop to leave is already reported.
+        let Some(target_id) = self.loop_targets.get(break_expr_node.id) else {
+            if let Some(value) = value {
+                self.check_expr(value);
+            }
+
+            return;
+        };
+
+        let target_loop = self.loop_by_id[&target_id];
+
+        if !target_loop.is_infinite {
+            if let Some(value) = value {
+                self.ctx.report(
+                    Diagnostic::error(
+                        "`break` with a value in a `for` that can end on its own",
+                        span,
+                    )
+                    .with_note(
+                        "only a `for` without a condition or range can give a value through \
+                         `break`",
+                    ),
+                );
+
+                self.check_expr(value);
+            }
+
+            return;
+        }
+
+        let (value_ty, value_span) = match value {
+            Some(value) => {
+                let value_ty =
+                    self.check_expr_expecting(value, target_loop.break_ty.or(target_loop.expected));
+
+                (value_ty, value.span)
+            }
+            None => (Ty::UNIT, span),
+        };
+
+        match target_loop.break_ty {
+            Some(break_ty) => self.expect_ty(value_ty, break_ty, value_span),
+            None if !matches!(value_ty, Ty::Never | Ty::Error) => {
+                self.loop_by_id
+                    .get_mut(&target_id)
+                    .expect("the loop is still being checked")
+                    .break_ty = Some(value_ty);
+            }
+            None => {}
+        }
+    }
+
+    fn check_cond_expr(&mut self, cond_expr: &'ctx Expr<'ctx>) {
+        let cond_ty = self.check_expr(cond_expr);
+        self.expect_ty(cond_ty, Ty::BOOL, cond_expr.span);
+    }
+
+    fn check_return_expr(&mut self, value: Option<&'ctx Expr<'ctx>>, span: Span) {
+        match value {
+            Some(value) => {
+                let value_ty = self.check_expr_expecting(value, Some(self.return_ty));
+                self.expect_ty(value_ty, self.return_ty, value.span);
+            }
+            None => {
+                if !Ty::UNIT.fits(self.return_ty) {
+                    self.ctx.report(Diagnostic::error(
+                        format!(
+                            "`return` without a value in a function returning `{}`",
+                            self.display_ty(self.return_ty)
+                        ),
+                        span,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Finds the type of a block, which is the type of its tail expression.
+    /// Only the tail is expected to have the `expected` type; the values of
+    /// the other expressions are thrown away, and so is the tail's if
+    /// `is_discarded`. A block whose value is `()` but that always leaves
+    /// early, such as through `return`, never produces a value.
+    fn check_compound_expr(
+        &mut self,
+        compound_expr: CompoundExpr<'ctx>,
+        expected: Option<Ty>,
+        is_discarded: bool,
+    ) -> Ty {
+        let mut block_ty = Ty::UNIT;
+        let mut diverges = false;
+
+        for (index, expr) in compound_expr.exprs.iter().enumerate() {
+            let is_tail = index + 1 == compound_expr.exprs.len();
+
+            if !is_tail || is_discarded {
+                self.discarded_values.insert(expr.id);
+            }
+            block_ty = self.check_expr_expecting(expr, expected.filter(|_| is_tail));
+
+            let leaves_early = match expr.kind {
+                ExprKind::Semi(expr) => self.types.get(expr.id) == Ty::Never,
+                _ => block_ty == Ty::Never,
+            };
+
+            diverges |= leaves_early;
+        }
+
+        if diverges && block_ty == Ty::UNIT {
+            Ty::Never
+        } else {
+            block_ty
+        }
+    }
+
+    fn check_fn_call_expr(&mut self, call_expr: &Expr, fn_call_expr: FnCallExpr<'ctx>) -> Ty {
+        let callee_ty = self.ty_of_reference(call_expr.id, call_expr.span);
+
+        let function_id = match callee_ty {
+            Ty::Function(function_id) => Some(function_id),
+            Ty::Error => None,
+            _ => {
+                self.ctx.report(Diagnostic::error(
+                    format!(
+                        "expected a function, found `{}`",
+                        self.display_ty(callee_ty)
+                    ),
+                    fn_call_expr.identifier_span,
+                ));
+
+                None
+            }
+        };
+
+        let parameters = function_id.map_or(&[][..], |function_id| {
+            self.function_by_id[&function_id].parameters
+        });
+
+        for (index, argument) in fn_call_expr.arguments.iter().enumerate() {
+            let param_ty = parameters.get(index).map(|param| Ty::Value(param.ty));
+            let argument_ty = self.check_expr_expecting(argument, param_ty);
+
+            if let Some(param_ty) = param_ty {
+                self.expect_ty(argument_ty, param_ty, argument.span);
+            }
+        }
+
+        let Some(function_id) = function_id else {
+            return Ty::Error;
+        };
+
+        let function = self.function_by_id[&function_id];
+
+        if function.parameters.len() != fn_call_expr.arguments.len() {
+            let mut diagnostic = Diagnostic::error(
+                format!(
+                    "function `{}` takes {} argument(s) but {} were supplied",
+                    self.ctx.resolve_symbol(fn_call_expr.identifier),
+                    function.parameters.len(),
+                    fn_call_expr.arguments.len()
+                ),
+                call_expr.span,
+            );
+
+            if let Some(&decl_span) = self.decl_span_by_function.get(&function_id) {
+                diagnostic = diagnostic.with_label(decl_span, "function defined here");
+            }
+
+            self.ctx.report(diagnostic);
+        }
+
+        Ty::Value(function.return_type)
+    }
+
+    fn check_binary_expr(&mut self, binary_expr: BinaryExpr<'ctx>, expected: Option<Ty>) -> Ty {
+        let (lhs, rhs) = (binary_expr.lhs, binary_expr.rhs);
+
+        match binary_expr.op {
+            BinaryOp::Mul
+            | BinaryOp::Div
+            | BinaryOp::Add
+            | BinaryOp::Sub
+            | BinaryOp::Shl
+            | BinaryOp::Shr => self.check_operands(lhs, rhs, expected, false),
+            BinaryOp::Less | BinaryOp::Greater | BinaryOp::LessEqual | BinaryOp::GreaterEqual => {
+                self.check_operands(lhs, rhs, None, false);
+
+                Ty::BOOL
+            }
+            // Both integers and `bool`s can be compared for equality.
+            BinaryOp::Equal | BinaryOp::NotEqual => {
+                self.check_operands(lhs, rhs, None, true);
+
+                Ty::BOOL
+            }
+            BinaryOp::LogicalAnd | BinaryOp::LogicalOr => {
+                for operand in [lhs, rhs] {
+                    let operand_ty = self.check_expr_expecting(operand, Some(Ty::BOOL));
+                    self.expect_ty(operand_ty, Ty::BOOL, operand.span);
+                }
+
+                Ty::BOOL
+            }
+        }
+    }
+
+    /// Checks that the assigned value has the type of the binding, which must
+    /// be an integer for compound assignments such as `+=`.
+    fn check_assign_expr(&mut self, assign_expr_node: &Expr, assign_expr: AssignExpr<'ctx>) {
+        let binding_ty = self.ty_of_reference(assign_expr_node.id, assign_expr_node.span);
+
+        if let Some(op) = assign_expr.op {
+            let is_integer =
+                binding_ty.int_type().is_some() || matches!(binding_ty, Ty::Never | Ty::Error);
+
+            if !is_integer {
+                self.ctx.report(Diagnostic::error(
+                    format!(
+                        "cannot apply operator `{}=` to type `{}`",
+                        binary_op_symbol(op),
+                        self.display_ty(binding_ty)
+                    ),
+                    assign_expr_node.span,
+                ));
+
+                self.check_expr(assign_expr.value);
+
+                return;
+            }
+        }
+
+        let value_ty = self.check_expr_expecting(assign_expr.value, Some(binding_ty));
+        self.expect_ty(value_ty, binding_ty, assign_expr.value.span);
+    }
+
+    /// Checks a pair of operands that must have the same integer type, or be
+    /// `bool`s if `accepts_bool` is set, and returns that type. An integer
+    /// constant without a suffix takes its type from the other operand, so
+    /// it's checked last.
+    fn check_operands(
+        &mut self,
+        lhs: &'ctx Expr<'ctx>,
+        rhs: &'ctx Expr<'ctx>,
+        expected: Option<Ty>,
+        accepts_bool: bool,
+    ) -> Ty {
+        let (first, second) = if is_unsuffixed_integer(lhs) {
+            (rhs, lhs)
+        } else {
+            (lhs, rhs)
+        };
+
+        let first_ty = self.check_expr_expecting(first, expected);
+
+        let is_accepted = first_ty.int_type().is_some()
+            || matches!(first_ty, Ty::Never | Ty::Error)
+            || (accepts_bool && first_ty == Ty::BOOL);
+
+        if !is_accepted {
+            self.ctx.report(Diagnostic::error(
+                format!(
+                    "mismatched types: expected {}, found `{}`",
+                    if accepts_bool {
+                        "an integer or `bool`"
+                    } else {
+                        "an integer"
+                    },
+                    self.display_ty(first_ty)
+                ),
+                first.span,
+            ));
+
+            self.check_expr(second);
+
+            return Ty::Error;
+        }
+
+        let second_ty = self.check_expr_expecting(second, Some(first_ty));
+        self.expect_ty(second_ty, first_ty, second.span);
+
+        first_ty
+    }
+
+    fn check_unary_expr(
+        &mut self,
+        unary_expr_node: &Expr,
+        unary_expr: UnaryExpr<'ctx>,
+        expected: Option<Ty>,
+    ) -> Ty {
+        let operand = unary_expr.operand;
+
+        match unary_expr.op {
+            UnaryOp::Neg => {
+                let operand_ty = match operand.kind {
+                    // A negated constant is checked with its sign, so that `-128i8` fits in
+                    // its type even though `128i8` doesn't.
+                    ExprKind::Const(Const::IntegerConstant { value, suffix }) => {
+                        let ty = self.check_integer_constant(
+                            value,
+                            suffix,
+                            true,
+                            expected,
+                            unary_expr_node.span,
+                        );
+                        self.types.ty_by_node.insert(operand.id, ty);
+
+                        ty
+                    }
+                    _ => self.check_expr_expecting(operand, expected),
+                };
+
+                let is_signed = operand_ty.int_type().map_or(
+                    matches!(operand_ty, Ty::Never | Ty::Error),
+                    IntType::is_signed,
+                );
+
+                if !is_signed {
+                    self.ctx.report(Diagnostic::error(
+                        format!(
+                            "cannot apply unary operator `-` to type `{}`",
+                            self.display_ty(operand_ty)
+                        ),
+                        unary_expr_node.span,
+                    ));
+
+                    return Ty::Error;
+                }
+
+                operand_ty
+            }
+            UnaryOp::Not => {
+                let operand_ty = self.check_expr_expecting(operand, Some(Ty::BOOL));
+                self.expect_ty(operand_ty, Ty::BOOL, operand.span);
+
+                Ty::BOOL
+            }
+        }
+    }
+
+    /// Integers can be cast to any integer type, and so can `bool`s, but
+    /// nothing can be cast to `bool`, which only comes from comparisons.
+    fn check_cast_expr(&mut self, cast_expr_node: &Expr, cast_expr: CastExpr<'ctx>) -> Ty {
+        let operand_ty = self.check_expr(cast_expr.operand);
+        let target_ty = Ty::Value(cast_expr.ty);
+
+        let is_valid = match (operand_ty, cast_expr.ty) {
+            (Ty::Never | Ty::Error, _) => true,
+            (Ty::Value(Type::Int(_) | Type::Bool), Type::Int(_)) => true,
+            (operand_ty, _) => operand_ty == target_ty,
+        };
+
+        if !is_valid {
+            self.ctx.report(Diagnostic::error(
+                format!(
+                    "cannot cast `{}` as `{}`",
+                    self.display_ty(operand_ty),
+                    self.display_ty(target_ty)
+                ),
+                cast_expr_node.span,
+            ));
+        }
+
+        target_ty
+    }
+
+    /// Finds the type of an integer constant, from its suffix or else from the
+    /// expected type, and checks that its value fits in it. A `negated`
+    /// constant is the operand of `-`.
+    fn check_integer_constant(
+        &self,
+        value: u64,
+        suffix: Option<IntType>,
+        negated: bool,
+        expected: Option<Ty>,
+        span: Span,
+    ) -> Ty {
+        let int_type = suffix
+            .or_else(|| expected.and_then(Ty::int_type))
+            .unwrap_or(IntType::I32);
+
+        // Unsigned types can't be negated at all, which is reported elsewhere.
+        let value = if negated && int_type.is_signed() {
+            -i128::from(value)
+        } else {
+            i128::from(value)
+        };
+
+        if value < int_type.min_value() || value > int_type.max_value() {
+            self.ctx.report(
+                Diagnostic::error(
+                    format!("integer constant is out of range for `{}`", int_type.name()),
+                    span,
+                )
+                .with_note(format!(
+                    "the range of `{}` is `{}..={}`",
+                    int_type.name(),
+                    int_type.min_value(),
+                    int_type.max_value()
+                )),
+            );
+        }
+
+        Ty::Value(Type::Int(int_type))
+    }
+
+    fn check_top_level_value(&mut self, identifier: Symbol, value: &'ctx Expr<'ctx>) -> Ty {
+        self.values_being_checked.insert(identifier);
+        let ty = self.check_expr(value);
+        self.values_being_checked.remove(&identifier);
+
+        self.ty_by_definition
+            .entry(Definition::TopLevel(identifier))
+            .or_insert(ty);
+
+        ty
+    }
+
+    /// Finds the type of the definition that a `BindRef`, `Assign` or `FnCall`
+    /// expression refers to, checking the value of a constant or global first
+    /// if it's not checked yet.
+    fn ty_of_reference(&mut self, node_id: NodeId, span: Span) -> Ty {
+        let Some(definition) = self.name_resolution.get(node_id) else {
+            return Ty::Error;
+        };
+
+        if let Some(&ty) = self.ty_by_definition.get(&definition) {
+            return ty;
+        }
+
+        match definition {
+            Definition::TopLevel(identifier) if self.values_being_checked.contains(&identifier) => {
+                self.ctx.report(Diagnostic::error(
+                    format!(
+                        "the value of `{}` depends on itself",
+                        self.ctx.resolve_symbol(identifier)
+                    ),
+                    span,
+                ));
+
+                Ty::Error
+            }
+            Definition::TopLevel(identifier) => match self.value_by_symbol.get(&identifier) {
+                Some(&value) => self.check_top_level_value(identifier, value),
+                None => Ty::Error,
+            },
+            _ => Ty::Error,
+        }
+    }
+
+    fn expect_ty(&self, actual_ty: Ty, expected_ty: Ty, span: Span) {
+        if !actual_ty.fits(expected_ty) {
+            self.ctx.report(Diagnostic::error(
+                format!(
+                    "mismatched types: expected `{}`, found `{}`",
+                    self.display_ty(expected_ty),
+                    self.display_ty(actual_ty)
+                ),
+                span,
+            ));
+        }
+    }
+
+    fn display_ty(&self, ty: Ty) -> String {
+        match ty {
+            Ty::Value(ty) => display_type(ty).to_owned(),
+            Ty::Function(function_id) => {
+                let function = self.function_by_id[&function_id];
+                let parameters = function
+                    .parameters
+                    .iter()
+                    .map(|param| display_type(param.ty))
+                    .collect::<Vec<_>>();
+
+                format!(
+                    "({}) -> {}",
+                    parameters.join(", "),
+                    display_type(function.return_type)
+                )
+            }
+            Ty::Never => "!".to_owned(),
+            Ty::Error => "{error}".to_owned(),
+        }
+    }
+}
+
+fn display_type(ty: Type) -> &'static str {
+    match ty {
+        Type::Unit => "()",
+        Type::Bool => "bool",
+        Type::Int(int_type) => int_type.name(),
+    }
+}
+
+pub(crate) fn binary_op_symbol(op: BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Mul => "*",
+        BinaryOp::Div => "/",
+        BinaryOp::Add => "+",
+        BinaryOp::Sub => "-",
+        BinaryOp::Shl => "<<",
+        BinaryOp::Shr => ">>",
+        BinaryOp::Less => "<",
+        BinaryOp::Greater => ">",
+        BinaryOp::LessEqual => "<=",
+        BinaryOp::GreaterEqual => ">=",
+        BinaryOp::Equal => "==",
+        BinaryOp::NotEqual => "!=",
+        BinaryOp::LogicalAnd => "&&",
+        BinaryOp::LogicalOr => "||",
+    }
+}
+
+/// Tells whether the expression is an integer constant without a suffix,
+/// possibly negated, whose type is inferred from where it's used.
+fn is_unsuffixed_integer(expr: &Expr) -> bool {
+    match expr.kind {
+        ExprKind::Const(Const::IntegerConstant { suffix: None, .. }) => true,
+        ExprKind::Unary(UnaryExpr {
+            op: UnaryOp::Neg,
+            operand,
+        }) => is_unsuffixed_integer(operand),
+        _ => false,
+    }
+}